@@ -0,0 +1,113 @@
+use tauri_specta::{collect_commands, collect_events, Builder, ErrorHandlingMode};
+
+/// Generated TypeScript bindings, relative to the crate root.
+#[cfg(test)]
+pub const BINDINGS_PATH: &str = "../src/bindings.ts";
+
+/// Every command and event the webviews use. Their argument, result and
+/// payload types are exported along with them.
+pub fn builder() -> Builder<tauri::Wry> {
+    Builder::<tauri::Wry>::new()
+        // Commands reject with the error string, like a plain `invoke`.
+        .error_handling(ErrorHandlingMode::Throw)
+        .commands(collect_commands![
+            crate::start_recording,
+            crate::stop_recording,
+            crate::start_recording_with_profile,
+            crate::list_profiles,
+            crate::create_profile,
+            crate::update_profile,
+            crate::delete_profile,
+            crate::export_profiles,
+            crate::import_profiles,
+            crate::start_replay_buffer,
+            crate::stop_replay_buffer,
+            crate::get_replay_status,
+            crate::save_replay,
+            crate::get_input_devices,
+            crate::toggle_webcam,
+            crate::open_folder,
+            crate::open_file,
+            crate::list_recordings,
+            crate::search_recordings,
+            crate::watch_library,
+            crate::delete_recording,
+            crate::list_trash,
+            crate::restore_recording,
+            crate::empty_trash,
+            crate::preview_retention,
+            crate::apply_retention,
+            crate::rename_recording,
+            crate::update_recording_metadata,
+            crate::list_tags,
+            crate::start_global_listener,
+            crate::get_disk_info,
+            crate::select_folder,
+            crate::get_hotkeys,
+            crate::get_settings,
+            crate::update_settings,
+            crate::add_marker,
+            crate::trim_recording,
+            crate::detect_silence,
+            crate::get_silence_analysis,
+            crate::export_without_silence,
+            crate::export_recording,
+            crate::transcribe_recording,
+            crate::get_transcript,
+            crate::list_jobs,
+            crate::cancel_job,
+            crate::retry_job,
+        ])
+        .events(collect_events![
+            crate::GlobalClick,
+            crate::GlobalKey,
+            crate::LowDiskWarning,
+            crate::MarkerAdded,
+            crate::RecordingStarted,
+            crate::RecordingStopped,
+            crate::ReplayBufferChanged,
+            crate::hotkeys::HotkeyAction,
+            crate::jobs::JobProgress,
+            crate::settings::SettingsChanged,
+            crate::watcher::LibraryChanged,
+        ])
+}
+
+#[cfg(test)]
+/// The contents of `src/bindings.ts`. Sizes and timestamps stay well below
+/// 2^53, so 64-bit integers are exported as plain numbers.
+pub fn export() -> Result<String, String> {
+    use specta_typescript::{BigIntExportBehavior, Typescript};
+
+    builder()
+        .export_str(
+            Typescript::default()
+                // The generated glue imports helpers it doesn't always use.
+                .header("// @ts-nocheck")
+                .bigint(BigIntExportBehavior::Number),
+        )
+        .map_err(|e| format!("Failed to export bindings: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    // Fails when a Rust type changed without regenerating the bindings.
+    // `UPDATE_BINDINGS=1 cargo test bindings` rewrites the file instead.
+    #[test]
+    fn bindings_are_up_to_date() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(BINDINGS_PATH);
+        let expected = export().unwrap();
+        if std::env::var_os("UPDATE_BINDINGS").is_some() {
+            std::fs::write(&path, &expected).unwrap();
+            return;
+        }
+        let actual = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            actual.replace("\r\n", "\n") == expected,
+            "src/bindings.ts is out of date; regenerate it with `UPDATE_BINDINGS=1 cargo test bindings`"
+        );
+    }
+}
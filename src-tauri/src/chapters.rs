@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::Path;
+
+/// A point of interest dropped while recording, relative to the start of the
+/// capture.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Marker {
+    pub time_ms: u64,
+    pub label: String,
+}
+
+// Special characters in FFMETADATA values must be backslash-escaped.
+fn escape_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Renders markers as an FFMETADATA1 document. Each marker starts a chapter
+/// that runs until the next one; a leading chapter is added when the first
+/// marker isn't at zero so the whole timeline is covered.
+pub fn ffmetadata(markers: &[Marker], duration_ms: u64) -> String {
+    let mut sorted: Vec<&Marker> = markers.iter().filter(|m| m.time_ms < duration_ms).collect();
+    sorted.sort_by_key(|m| m.time_ms);
+
+    let intro = Marker {
+        time_ms: 0,
+        label: "Start".to_string(),
+    };
+    if sorted.first().is_none_or(|m| m.time_ms > 0) {
+        sorted.insert(0, &intro);
+    }
+
+    let mut doc = String::from(";FFMETADATA1\n");
+    for (i, marker) in sorted.iter().enumerate() {
+        let end = sorted.get(i + 1).map_or(duration_ms, |next| next.time_ms);
+        if end <= marker.time_ms {
+            continue;
+        }
+        doc.push_str("\n[CHAPTER]\nTIMEBASE=1/1000\n");
+        doc.push_str(&format!("START={}\nEND={}\n", marker.time_ms, end));
+        doc.push_str(&format!("title={}\n", escape_value(&marker.label)));
+    }
+    doc
+}
+
+/// Rewrites `video_path` in place with the markers embedded as container
+/// chapters. Streams are copied, so this only takes as long as the file I/O.
+pub fn embed_chapters(
+    video_path: &Path,
+    markers: &[Marker],
+    duration_ms: u64,
+) -> Result<(), String> {
+    let session_dir = video_path
+        .parent()
+        .ok_or_else(|| "Recording has no parent folder".to_string())?;
+    let meta_path = session_dir.join("chapters.ffmeta");
+    fs::write(&meta_path, ffmetadata(markers, duration_ms))
+        .map_err(|e| format!("Failed to write chapter metadata: {}", e))?;
+
+    let ext = video_path
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mp4".to_string());
+    let temp_path = video_path.with_extension(format!("chapters.{}", ext));
+
+    let mut command = crate::background_command("ffmpeg");
+    command
+        .arg("-y")
+        .arg("-i")
+        .arg(video_path)
+        .arg("-i")
+        .arg(&meta_path)
+        .args([
+            "-map",
+            "0",
+            "-map_metadata",
+            "1",
+            "-map_chapters",
+            "1",
+            "-c",
+            "copy",
+        ]);
+    // Only the MP4 family has a moov atom to move; MKV rejects the flag.
+    if matches!(ext.to_ascii_lowercase().as_str(), "mp4" | "mov" | "m4v") {
+        command.args(["-movflags", "+faststart"]);
+    }
+    let output = command
+        .arg(&temp_path)
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    let _ = fs::remove_file(&meta_path);
+
+    if !output.status.success() {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!(
+            "ffmpeg failed to embed chapters: {}",
+            String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .last()
+                .unwrap_or("")
+        ));
+    }
+
+    fs::rename(&temp_path, video_path).map_err(|e| format!("Failed to replace recording: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marker(time_ms: u64, label: &str) -> Marker {
+        Marker {
+            time_ms,
+            label: label.into(),
+        }
+    }
+
+    #[test]
+    fn escapes_special_characters_in_titles() {
+        assert_eq!(escape_value("a=b;c#d\\e\nf"), "a\\=b\\;c\\#d\\\\e\\\nf");
+        assert_eq!(escape_value("Plain title"), "Plain title");
+    }
+
+    #[test]
+    fn chapters_run_until_the_next_marker() {
+        let doc = ffmetadata(&[marker(5_000, "Demo"), marker(2_000, "Intro")], 9_000);
+        assert_eq!(
+            doc,
+            ";FFMETADATA1\n\
+             \n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=2000\ntitle=Start\n\
+             \n[CHAPTER]\nTIMEBASE=1/1000\nSTART=2000\nEND=5000\ntitle=Intro\n\
+             \n[CHAPTER]\nTIMEBASE=1/1000\nSTART=5000\nEND=9000\ntitle=Demo\n"
+        );
+    }
+
+    #[test]
+    fn drops_markers_past_the_end_and_empty_chapters() {
+        let markers = [
+            marker(0, "Begin"),
+            marker(3_000, "Twice"),
+            marker(3_000, "Twice again"),
+            marker(12_000, "After the end"),
+        ];
+        let doc = ffmetadata(&markers, 10_000);
+        // No "Start" chapter when a marker sits at zero, and the duplicate
+        // at 3s would be zero length.
+        assert!(!doc.contains("title=Start"));
+        assert!(!doc.contains("After the end"));
+        assert_eq!(doc.matches("[CHAPTER]").count(), 2);
+        assert!(doc.contains("START=3000\nEND=10000\ntitle=Twice again\n"));
+    }
+}
@@ -0,0 +1,218 @@
+use serde::Serialize;
+use specta::Type;
+use std::path::{Path, PathBuf};
+
+/// Recordings stop when free space drops below this unless configured.
+pub const DEFAULT_LOW_DISK_MB: u64 = 1024;
+/// Upper bound for the setting; far above any real drive's free space.
+pub const MAX_LOW_DISK_MB: u64 = 1024 * 1024 * 1024;
+/// Starting a recording warns when fewer minutes than this fit on the disk.
+pub const PREFLIGHT_WARN_MINUTES: u64 = 10;
+// Roughly what x264 superfast at CRF 23 spends on screen content; busy
+// footage runs higher, static desktops far lower.
+const BITS_PER_PIXEL: f64 = 0.08;
+
+#[derive(Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskInfo {
+    /// Bytes available to this user, which honours quotas.
+    pub free: u64,
+    pub total: u64,
+    pub label: String,
+}
+
+// The save folder may not exist yet; its nearest existing ancestor lives on
+// the same volume.
+fn existing_ancestor(path: &Path) -> PathBuf {
+    path.ancestors()
+        .find(|p| p.exists())
+        .unwrap_or(path)
+        .to_path_buf()
+}
+
+/// Free space on the volume holding `path`, in bytes.
+pub fn free_space(path: &Path) -> Result<u64, String> {
+    platform::space(&existing_ancestor(path)).map(|(free, _)| free)
+}
+
+/// Estimated size of a recording per second at this resolution and rate.
+pub fn recording_bytes_per_second(width: u32, height: u32, fps: u32, audio_kbps: u32) -> u64 {
+    let video_bits = width as f64 * height as f64 * fps as f64 * BITS_PER_PIXEL;
+    ((video_bits + audio_kbps as f64 * 1000.0) / 8.0) as u64
+}
+
+/// Free/total space and a display label for the volume holding `path`.
+pub fn disk_info(path: &Path) -> Result<DiskInfo, String> {
+    let path = existing_ancestor(path);
+    let (free, total) = platform::space(&path)?;
+    Ok(DiskInfo {
+        free,
+        total,
+        label: platform::label(&path),
+    })
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+    use windows_sys::Win32::Storage::FileSystem::{
+        GetDiskFreeSpaceExW, GetVolumeInformationW, GetVolumePathNameW,
+    };
+
+    fn wide(path: &Path) -> Vec<u16> {
+        path.as_os_str().encode_wide().chain(Some(0)).collect()
+    }
+
+    fn from_wide(buffer: &[u16]) -> String {
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        String::from_utf16_lossy(&buffer[..len])
+    }
+
+    pub fn space(path: &Path) -> Result<(u64, u64), String> {
+        let (mut free, mut total) = (0u64, 0u64);
+        let ok = unsafe {
+            GetDiskFreeSpaceExW(
+                wide(path).as_ptr(),
+                &mut free,
+                &mut total,
+                std::ptr::null_mut(),
+            )
+        };
+        if ok == 0 {
+            return Err(format!(
+                "Failed to read disk space: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok((free, total))
+    }
+
+    // "Data (D:)", or "Local Disk (D:)" for volumes without a name.
+    pub fn label(path: &Path) -> String {
+        let mut root = [0u16; 261];
+        let ok = unsafe {
+            GetVolumePathNameW(wide(path).as_ptr(), root.as_mut_ptr(), root.len() as u32)
+        };
+        if ok == 0 {
+            return "Local Disk".into();
+        }
+        let mut name = [0u16; 261];
+        let ok = unsafe {
+            GetVolumeInformationW(
+                root.as_ptr(),
+                name.as_mut_ptr(),
+                name.len() as u32,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        let root = from_wide(&root);
+        let drive = root.trim_end_matches('\\');
+        let name = if ok != 0 {
+            from_wide(&name)
+        } else {
+            String::new()
+        };
+        if name.is_empty() {
+            format!("Local Disk ({})", drive)
+        } else {
+            format!("{} ({})", name, drive)
+        }
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    // Field widths differ between platforms, hence the casts.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn space(path: &Path) -> Result<(u64, u64), String> {
+        let c_path = CString::new(path.as_os_str().as_bytes()).map_err(|e| e.to_string())?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return Err(format!(
+                "Failed to read disk space: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        let block = stat.f_frsize as u64;
+        Ok((stat.f_bavail as u64 * block, stat.f_blocks as u64 * block))
+    }
+
+    // /proc/self/mounts escapes spaces and friends as octal ("\040") and
+    // udev's by-label links as hex ("\x20").
+    #[cfg(target_os = "linux")]
+    fn unescape(field: &str) -> String {
+        let mut out = String::new();
+        let mut chars = field.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            let (radix, digits) = if chars.next_if_eq(&'x').is_some() {
+                (16, 2)
+            } else {
+                (8, 3)
+            };
+            let code: String = chars.by_ref().take(digits).collect();
+            match u8::from_str_radix(&code, radix) {
+                Ok(byte) => out.push(byte as char),
+                Err(_) => out.push_str(&code),
+            }
+        }
+        out
+    }
+
+    // The filesystem label from /dev/disk/by-label when the device has one,
+    // otherwise the mount point, e.g. "Recordings (/mnt/data)" or "/home".
+    #[cfg(target_os = "linux")]
+    pub fn label(path: &Path) -> String {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let mounts = std::fs::read_to_string("/proc/self/mounts").unwrap_or_default();
+        let Some((device, mount_point)) = mounts
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                Some((unescape(fields.next()?), unescape(fields.next()?)))
+            })
+            .filter(|(_, mount_point)| path.starts_with(mount_point))
+            .max_by_key(|(_, mount_point)| mount_point.len())
+        else {
+            return "/".into();
+        };
+
+        let device = Path::new(&device)
+            .canonicalize()
+            .unwrap_or_else(|_| device.into());
+        let name = std::fs::read_dir("/dev/disk/by-label")
+            .into_iter()
+            .flatten()
+            .flatten()
+            .find(|link| {
+                link.path()
+                    .canonicalize()
+                    .is_ok_and(|target| target == device)
+            })
+            .map(|link| unescape(&link.file_name().to_string_lossy()));
+        match name {
+            Some(name) => format!("{} ({})", name, mount_point),
+            None => mount_point,
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn label(path: &Path) -> String {
+        path.ancestors()
+            .last()
+            .map(|root| root.display().to_string())
+            .unwrap_or_default()
+    }
+}
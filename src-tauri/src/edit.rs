@@ -0,0 +1,330 @@
+use crate::chapters::{self, Marker};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A span of the source recording to keep, in milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct KeepRange {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// A slice of the source played back at `speed` (1.0 = unchanged).
+#[derive(Clone, Copy, Debug)]
+pub struct Segment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub speed: f64,
+}
+
+impl From<&KeepRange> for Segment {
+    fn from(range: &KeepRange) -> Self {
+        Segment {
+            start_ms: range.start_ms,
+            end_ms: range.end_ms,
+            speed: 1.0,
+        }
+    }
+}
+
+impl Segment {
+    fn output_ms(&self) -> u64 {
+        ((self.end_ms - self.start_ms) as f64 / self.speed).round() as u64
+    }
+}
+
+fn default_edit_kind() -> String {
+    "trim".to_string()
+}
+
+/// A file derived from the original recording, as listed in the manifest.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditRecord {
+    /// "trim", "silenceRemoved" or "silenceSpedUp".
+    #[serde(default = "default_edit_kind")]
+    pub kind: String,
+    pub file: String,
+    pub ranges: Vec<KeepRange>,
+    pub precise: bool,
+    pub duration_ms: u64,
+    pub created_at: u64,
+}
+
+/// Claims a file name `<prefix>_<timestamp>.mp4` in `dir` for an edit by
+/// creating it empty, adding `_2` and up when taken, so jobs finishing in
+/// the same second never write to the same file.
+pub fn claim_output(dir: &Path, prefix: &str) -> Result<String, String> {
+    let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+    for n in 1.. {
+        let file = if n == 1 {
+            format!("{}_{}.mp4", prefix, timestamp)
+        } else {
+            format!("{}_{}_{}.mp4", prefix, timestamp, n)
+        };
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(dir.join(&file))
+        {
+            Ok(_) => return Ok(file),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(format!("Failed to create {}: {}", file, e)),
+        }
+    }
+    unreachable!("ran out of file names")
+}
+
+/// Sorts the ranges, clamps them to the recording and merges overlaps.
+pub fn normalize_ranges(ranges: &[KeepRange], duration_ms: u64) -> Result<Vec<KeepRange>, String> {
+    let mut sorted: Vec<KeepRange> = ranges
+        .iter()
+        .map(|r| KeepRange {
+            start_ms: r.start_ms,
+            end_ms: if duration_ms > 0 {
+                r.end_ms.min(duration_ms)
+            } else {
+                r.end_ms
+            },
+        })
+        .filter(|r| r.end_ms > r.start_ms)
+        .collect();
+    sorted.sort_by_key(|r| r.start_ms);
+
+    let mut merged: Vec<KeepRange> = Vec::with_capacity(sorted.len());
+    for range in sorted {
+        match merged.last_mut() {
+            Some(last) if range.start_ms <= last.end_ms => {
+                last.end_ms = last.end_ms.max(range.end_ms)
+            }
+            _ => merged.push(range),
+        }
+    }
+
+    if merged.is_empty() {
+        return Err("No valid ranges to keep".to_string());
+    }
+    Ok(merged)
+}
+
+/// Moves markers that fall inside kept segments onto the edited timeline.
+pub fn remap_markers(markers: &[Marker], segments: &[Segment]) -> Vec<Marker> {
+    let mut remapped = Vec::new();
+    let mut offset = 0;
+    for segment in segments {
+        for marker in markers {
+            if marker.time_ms >= segment.start_ms && marker.time_ms < segment.end_ms {
+                let into = (marker.time_ms - segment.start_ms) as f64 / segment.speed;
+                remapped.push(Marker {
+                    time_ms: offset + into.round() as u64,
+                    label: marker.label.clone(),
+                });
+            }
+        }
+        offset += segment.output_ms();
+    }
+    remapped
+}
+
+fn secs(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+// Input seeking with stream copy snaps each cut to the previous keyframe,
+// then the parts are joined with the concat demuxer. Copying is quick, so
+// progress and cancellation are checked between parts.
+fn trim_copy(
+    source: &Path,
+    ranges: &[KeepRange],
+    target: &Path,
+    cancel: &AtomicBool,
+    on_progress: &mut dyn FnMut(f64),
+) -> Result<(), String> {
+    let work_dir = target.with_extension("parts");
+    fs::create_dir_all(&work_dir).map_err(|e| e.to_string())?;
+
+    let result = (|| {
+        let mut list = String::new();
+        for (i, range) in ranges.iter().enumerate() {
+            if cancel.load(Ordering::SeqCst) {
+                return Err(crate::CANCELLED.to_string());
+            }
+            on_progress(i as f64 / (ranges.len() + 1) as f64);
+            let part_name = format!("part{}.mp4", i);
+            crate::run_ffmpeg([
+                "-y".into(),
+                "-ss".into(),
+                secs(range.start_ms),
+                "-i".into(),
+                source.to_string_lossy().to_string(),
+                "-t".into(),
+                secs(range.end_ms - range.start_ms),
+                "-map".into(),
+                "0".into(),
+                "-map_chapters".into(),
+                "-1".into(),
+                "-c".into(),
+                "copy".into(),
+                "-avoid_negative_ts".into(),
+                "make_zero".into(),
+                work_dir.join(&part_name).to_string_lossy().to_string(),
+            ])?;
+            list.push_str(&format!("file '{}'\n", part_name));
+        }
+
+        let list_path = work_dir.join("parts.txt");
+        fs::write(&list_path, list).map_err(|e| e.to_string())?;
+        crate::run_ffmpeg([
+            "-y".into(),
+            "-f".into(),
+            "concat".into(),
+            "-safe".into(),
+            "0".into(),
+            "-i".into(),
+            list_path.to_string_lossy().to_string(),
+            "-c".into(),
+            "copy".into(),
+            "-movflags".into(),
+            "+faststart".into(),
+            target.to_string_lossy().to_string(),
+        ])
+    })();
+
+    let _ = fs::remove_dir_all(&work_dir);
+    result
+}
+
+// atempo only accepts factors up to 2.0 on older FFmpeg builds, so larger
+// speed-ups are chained.
+fn atempo_chain(speed: f64) -> String {
+    let mut remaining = speed;
+    let mut parts = Vec::new();
+    while remaining > 2.0 {
+        parts.push("atempo=2.0".to_string());
+        remaining /= 2.0;
+    }
+    parts.push(format!("atempo={:.4}", remaining));
+    parts.join(",")
+}
+
+/// Frame-accurate: trims every segment in a filter graph, retimes it by its
+/// speed and re-encodes to H.264 (libx264, CRF 23) and AAC, whatever the
+/// source was recorded with.
+pub fn render_segments(
+    source: &Path,
+    segments: &[Segment],
+    has_audio: bool,
+    target: &Path,
+    cancel: &AtomicBool,
+    on_progress: &mut dyn FnMut(f64),
+) -> Result<(), String> {
+    let mut filter = String::new();
+    let mut concat_inputs = String::new();
+    for (i, segment) in segments.iter().enumerate() {
+        let (start, end) = (secs(segment.start_ms), secs(segment.end_ms));
+        let retimed = (segment.speed - 1.0).abs() > f64::EPSILON;
+        filter.push_str(&format!(
+            "[0:v]trim=start={}:end={},setpts={}[v{}];",
+            start,
+            end,
+            if retimed {
+                format!("(PTS-STARTPTS)/{:.4}", segment.speed)
+            } else {
+                "PTS-STARTPTS".to_string()
+            },
+            i
+        ));
+        concat_inputs.push_str(&format!("[v{}]", i));
+        if has_audio {
+            filter.push_str(&format!(
+                "[0:a]atrim=start={}:end={},asetpts=PTS-STARTPTS{}[a{}];",
+                start,
+                end,
+                if retimed {
+                    format!(",{}", atempo_chain(segment.speed))
+                } else {
+                    String::new()
+                },
+                i
+            ));
+            concat_inputs.push_str(&format!("[a{}]", i));
+        }
+    }
+    filter.push_str(&format!(
+        "{}concat=n={}:v=1:a={}[v]{}",
+        concat_inputs,
+        segments.len(),
+        if has_audio { 1 } else { 0 },
+        if has_audio { "[a]" } else { "" }
+    ));
+
+    let mut args: Vec<String> = vec![
+        "-y".into(),
+        "-i".into(),
+        source.to_string_lossy().to_string(),
+        "-filter_complex".into(),
+        filter,
+        "-map".into(),
+        "[v]".into(),
+    ];
+    if has_audio {
+        args.extend(["-map".into(), "[a]".into(), "-c:a".into(), "aac".into()]);
+        args.extend(["-b:a".into(), "192k".into()]);
+    }
+    args.extend(
+        [
+            "-c:v",
+            "libx264",
+            "-pix_fmt",
+            "yuv420p",
+            "-preset",
+            "superfast",
+            "-crf",
+            "23",
+            "-movflags",
+            "+faststart",
+        ]
+        .map(String::from),
+    );
+    args.push(target.to_string_lossy().to_string());
+    let duration_ms = segments.iter().map(Segment::output_ms).sum();
+    crate::run_ffmpeg_with_progress(args, target, duration_ms, cancel, on_progress)
+}
+
+/// Embeds already remapped markers as chapters of an edited file.
+pub fn embed_markers(target: &Path, segments: &[Segment], markers: &[Marker]) {
+    if markers.is_empty() {
+        return;
+    }
+    let duration_ms = segments.iter().map(Segment::output_ms).sum();
+    if let Err(e) = chapters::embed_chapters(target, markers, duration_ms) {
+        println!("Failed to embed chapters in edited file: {}", e);
+    }
+}
+
+/// Cuts `source` down to `ranges` (already normalized) into `target`, then
+/// embeds the markers that survive as chapters.
+#[allow(clippy::too_many_arguments)]
+pub fn trim(
+    source: &Path,
+    ranges: &[KeepRange],
+    precise: bool,
+    has_audio: bool,
+    markers: &[Marker],
+    target: &Path,
+    cancel: &AtomicBool,
+    on_progress: &mut dyn FnMut(f64),
+) -> Result<(), String> {
+    let segments: Vec<Segment> = ranges.iter().map(Segment::from).collect();
+    if precise {
+        render_segments(source, &segments, has_audio, target, cancel, on_progress)?;
+    } else {
+        trim_copy(source, ranges, target, cancel, on_progress)?;
+    }
+    embed_markers(target, &segments, &remap_markers(markers, &segments));
+    Ok(())
+}
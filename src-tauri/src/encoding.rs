@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// H.264 encoder used while recording. Hardware encoders need a matching GPU
+/// and driver; FFmpeg fails to start without one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum VideoEncoder {
+    /// Software x264, which works everywhere.
+    #[default]
+    X264,
+    /// NVIDIA NVENC.
+    Nvenc,
+    /// Intel Quick Sync.
+    Qsv,
+    /// AMD AMF.
+    Amf,
+}
+
+impl VideoEncoder {
+    /// Encoder arguments at roughly the same quality, with a keyframe every
+    /// `keyframe_interval` frames.
+    pub fn args(self, keyframe_interval: u32) -> Vec<String> {
+        let args: &[&str] = match self {
+            VideoEncoder::X264 => &[
+                "-c:v",
+                "libx264",
+                "-pix_fmt",
+                "yuv420p",
+                "-preset",
+                "superfast",
+                "-crf",
+                "23",
+            ],
+            VideoEncoder::Nvenc => &[
+                "-c:v",
+                "h264_nvenc",
+                "-pix_fmt",
+                "yuv420p",
+                "-preset",
+                "p4",
+                "-rc",
+                "vbr",
+                "-cq",
+                "23",
+                "-b:v",
+                "0",
+            ],
+            VideoEncoder::Qsv => &[
+                "-c:v",
+                "h264_qsv",
+                "-pix_fmt",
+                "nv12",
+                "-preset",
+                "veryfast",
+                "-global_quality",
+                "23",
+            ],
+            VideoEncoder::Amf => &[
+                "-c:v", "h264_amf", "-pix_fmt", "yuv420p", "-quality", "speed", "-rc", "cqp",
+                "-qp_i", "23", "-qp_p", "23",
+            ],
+        };
+        let mut args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        args.extend(["-g".to_string(), keyframe_interval.to_string()]);
+        args
+    }
+}
+
+/// File format recordings are written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum Container {
+    #[default]
+    Mp4,
+    /// Matroska stays playable when the app or FFmpeg dies mid-recording.
+    Mkv,
+}
+
+impl Container {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Container::Mp4 => "mp4",
+            Container::Mkv => "mkv",
+        }
+    }
+
+    /// Muxer name for `-segment_format`.
+    pub fn format(self) -> &'static str {
+        match self {
+            Container::Mp4 => "mp4",
+            Container::Mkv => "matroska",
+        }
+    }
+
+    /// Muxer options, e.g. moving the MP4 index up front for streaming.
+    pub fn mux_options(self) -> Option<&'static str> {
+        match self {
+            Container::Mp4 => Some("movflags=+faststart"),
+            Container::Mkv => None,
+        }
+    }
+}
+
+/// Checks a user-supplied video filter chain such as `hqdn3d,eq=gamma=1.1`.
+/// Graph syntax (labels, `;`) is rejected since the chain is applied with
+/// `-vf` after scaling.
+pub fn validate_filters(filters: &str) -> Result<(), String> {
+    if filters.trim().is_empty() {
+        return Err("Video filters are empty".into());
+    }
+    if filters.contains([';', '[', ']']) {
+        return Err("Video filters must be a simple comma-separated chain".into());
+    }
+    Ok(())
+}
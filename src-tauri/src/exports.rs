@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+pub const EXPORTS_DIR: &str = "exports";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum ExportPreset {
+    #[serde(rename = "gif")]
+    Gif,
+    #[serde(rename = "webm")]
+    Webm,
+    #[serde(rename = "mp4-720p")]
+    Mp4_720,
+    #[serde(rename = "mp4-1080p")]
+    Mp4_1080,
+    /// 9:16 center crop for shorts/reels.
+    #[serde(rename = "vertical")]
+    Vertical,
+    #[serde(rename = "mp3")]
+    Mp3,
+    #[serde(rename = "opus")]
+    Opus,
+}
+
+impl ExportPreset {
+    pub fn slug(self) -> &'static str {
+        match self {
+            ExportPreset::Gif => "gif",
+            ExportPreset::Webm => "webm",
+            ExportPreset::Mp4_720 => "720p",
+            ExportPreset::Mp4_1080 => "1080p",
+            ExportPreset::Vertical => "vertical",
+            ExportPreset::Mp3 => "mp3",
+            ExportPreset::Opus => "opus",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportPreset::Gif => "gif",
+            ExportPreset::Webm => "webm",
+            ExportPreset::Mp4_720 | ExportPreset::Mp4_1080 | ExportPreset::Vertical => "mp4",
+            ExportPreset::Mp3 => "mp3",
+            ExportPreset::Opus => "opus",
+        }
+    }
+
+    pub fn is_audio_only(self) -> bool {
+        matches!(self, ExportPreset::Mp3 | ExportPreset::Opus)
+    }
+
+    // Everything between the input and the output path. Scales never upscale
+    // and keep dimensions even for the encoders that need it.
+    fn output_args(self, has_audio: bool) -> Vec<String> {
+        let h264 = |filter: &'static str| -> Vec<&'static str> {
+            let mut args = vec!["-vf", filter];
+            args.extend([
+                "-c:v",
+                "libx264",
+                "-preset",
+                "medium",
+                "-crf",
+                "23",
+                "-pix_fmt",
+                "yuv420p",
+                "-movflags",
+                "+faststart",
+            ]);
+            args
+        };
+        let aac = ["-c:a", "aac", "-b:a", "160k"];
+
+        let mut args: Vec<&str> = match self {
+            // Two-pass palette in a single graph: palettegen on one branch,
+            // paletteuse on the other, which beats FFmpeg's default 256 colours.
+            ExportPreset::Gif => vec![
+                "-vf",
+                "fps=15,scale='min(960,iw)':-1:flags=lanczos,split[s0][s1];\
+                 [s0]palettegen=stats_mode=diff[p];[s1][p]paletteuse=dither=bayer:bayer_scale=5",
+                "-loop",
+                "0",
+                "-an",
+            ],
+            ExportPreset::Webm => {
+                let mut args = vec![
+                    "-c:v",
+                    "libvpx-vp9",
+                    "-b:v",
+                    "0",
+                    "-crf",
+                    "32",
+                    "-row-mt",
+                    "1",
+                    "-deadline",
+                    "good",
+                    "-cpu-used",
+                    "4",
+                ];
+                if has_audio {
+                    args.extend(["-c:a", "libopus", "-b:a", "128k"]);
+                }
+                args
+            }
+            ExportPreset::Mp4_720 => h264("scale=-2:'min(720,ih)'"),
+            ExportPreset::Mp4_1080 => h264("scale=-2:'min(1080,ih)'"),
+            ExportPreset::Vertical => {
+                h264("crop='trunc(ih*9/16/2)*2':ih,scale=-2:'min(1920,ih)',setsar=1")
+            }
+            ExportPreset::Mp3 => vec!["-vn", "-c:a", "libmp3lame", "-q:a", "2"],
+            ExportPreset::Opus => vec!["-vn", "-c:a", "libopus", "-b:a", "96k"],
+        };
+        if has_audio && matches!(self, Self::Mp4_720 | Self::Mp4_1080 | Self::Vertical) {
+            args.extend(aac);
+        } else if !has_audio && !matches!(self, Self::Gif | Self::Mp3 | Self::Opus) {
+            args.push("-an");
+        }
+        args.into_iter().map(String::from).collect()
+    }
+}
+
+/// Encodes `source` into `target` with the preset, reporting progress as a
+/// fraction of `duration_ms`. Returns `Err(CANCELLED)` when `cancel` is set.
+pub fn run_export(
+    source: &Path,
+    target: &Path,
+    preset: ExportPreset,
+    has_audio: bool,
+    duration_ms: u64,
+    cancel: &AtomicBool,
+    on_progress: &mut dyn FnMut(f64),
+) -> Result<(), String> {
+    if preset.is_audio_only() && !has_audio {
+        return Err("Recording has no audio to export".to_string());
+    }
+
+    let mut args = vec![
+        "-y".to_string(),
+        "-i".into(),
+        source.to_string_lossy().to_string(),
+    ];
+    args.extend(preset.output_args(has_audio));
+    args.push(target.to_string_lossy().to_string());
+    crate::run_ffmpeg_with_progress(args, target, duration_ms, cancel, on_progress)
+}
@@ -0,0 +1,164 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const OUTSIDE: &str = "Path is outside the save folder";
+
+/// The save root with symlinks and `..` resolved, created if missing.
+pub fn canonical_root(root: &Path) -> Result<PathBuf, String> {
+    fs::create_dir_all(root).map_err(|e| format!("Failed to create save folder: {}", e))?;
+    root.canonicalize()
+        .map_err(|e| format!("Failed to resolve save folder: {}", e))
+}
+
+/// Checks that `path` exists and resolves to somewhere inside `root`, so
+/// neither `..` nor a symlink can reach outside it. Returns the resolved
+/// path, so a symlink swapped in after the check can't redirect the caller.
+pub fn within(root: &Path, path: &Path) -> Result<PathBuf, String> {
+    let root = canonical_root(root)?;
+    let resolved = path
+        .canonicalize()
+        .map_err(|_| "File not found".to_string())?;
+    if resolved.starts_with(&root) {
+        Ok(resolved)
+    } else {
+        Err(OUTSIDE.into())
+    }
+}
+
+/// Whether `name` is a single `Session_*` path component.
+pub fn is_session_folder(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(name)), None) if name.to_string_lossy().starts_with("Session_")
+    )
+}
+
+/// The session folder for `id`, which is a `Session_*` folder name directly
+/// inside `root`.
+pub fn session_dir(root: &Path, id: &str) -> Result<PathBuf, String> {
+    if !is_session_folder(id) {
+        return Err(format!("Invalid recording id: {}", id));
+    }
+
+    let dir = root.join(id);
+    let canonical_root = canonical_root(root)?;
+    let resolved = dir
+        .canonicalize()
+        .map_err(|_| "Recording not found".to_string())?;
+    // A symlinked session folder has to point at another folder of the root.
+    if resolved.parent() != Some(canonical_root.as_path()) || !resolved.is_dir() {
+        return Err(OUTSIDE.into());
+    }
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("reframe-guard-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    // A save root with one session, next to a folder that must stay private.
+    fn library(name: &str) -> (TempDir, PathBuf, PathBuf) {
+        let temp = TempDir::new(name);
+        let root = temp.0.join("library");
+        let secret = temp.0.join("secret");
+        fs::create_dir_all(root.join("Session_1")).unwrap();
+        fs::write(root.join("Session_1").join("screen.mp4"), b"video").unwrap();
+        fs::create_dir_all(&secret).unwrap();
+        fs::write(secret.join("passwords.txt"), b"hunter2").unwrap();
+        (temp, root, secret)
+    }
+
+    #[test]
+    fn accepts_sessions_and_their_files() {
+        let (_temp, root, _) = library("accepts");
+        assert_eq!(
+            session_dir(&root, "Session_1").unwrap(),
+            root.join("Session_1")
+        );
+        assert!(within(&root, &root.join("Session_1").join("screen.mp4")).is_ok());
+        assert!(within(&root, &root).is_ok());
+    }
+
+    #[test]
+    fn rejects_ids_that_are_not_a_single_session_folder() {
+        let (_temp, root, secret) = library("ids");
+        for id in [
+            "",
+            ".",
+            "..",
+            "../secret",
+            "Session_1/../../secret",
+            "Session_1/screen.mp4",
+            "secret",
+            "Session_missing",
+        ] {
+            assert!(session_dir(&root, id).is_err(), "accepted {:?}", id);
+        }
+        assert!(session_dir(&root, &secret.to_string_lossy()).is_err());
+    }
+
+    #[test]
+    fn rejects_traversal_out_of_the_root() {
+        let (_temp, root, _) = library("traversal");
+        let escape = root
+            .join("Session_1")
+            .join("..")
+            .join("..")
+            .join("secret")
+            .join("passwords.txt");
+        assert!(escape.exists());
+        assert!(within(&root, &escape).is_err());
+        assert!(within(&root, &root.join("..")).is_err());
+        // `..` that stays inside the root is fine, and comes back resolved.
+        assert_eq!(
+            within(&root, &root.join("Session_1").join("..").join("Session_1")).unwrap(),
+            root.canonicalize().unwrap().join("Session_1")
+        );
+    }
+
+    #[test]
+    fn rejects_missing_paths() {
+        let (_temp, root, _) = library("missing");
+        assert!(within(&root, &root.join("Session_1").join("nope.mp4")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlink_escapes() {
+        use std::os::unix::fs::symlink;
+
+        let (_temp, root, secret) = library("symlinks");
+        symlink(&secret, root.join("Session_link")).unwrap();
+        symlink(
+            secret.join("passwords.txt"),
+            root.join("Session_1").join("screen_trim.mp4"),
+        )
+        .unwrap();
+        symlink(root.join("Session_1"), root.join("Session_alias")).unwrap();
+
+        assert!(session_dir(&root, "Session_link").is_err());
+        assert!(within(&root, &root.join("Session_link").join("passwords.txt")).is_err());
+        assert!(within(&root, &root.join("Session_1").join("screen_trim.mp4")).is_err());
+        // Links between sessions of the same library stay allowed.
+        assert!(session_dir(&root, "Session_alias").is_ok());
+    }
+}
@@ -0,0 +1,520 @@
+use rdev::Key;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Actions that can be bound to a global hotkey. Sent as the
+/// `hotkey-action` event for the ones the dashboard carries out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub enum HotkeyAction {
+    StartRecording,
+    StopRecording,
+    TogglePause,
+    AddMarker,
+    ToggleWebcam,
+}
+
+impl HotkeyAction {
+    fn label(self) -> &'static str {
+        match self {
+            HotkeyAction::StartRecording => "Start recording",
+            HotkeyAction::StopRecording => "Stop recording",
+            HotkeyAction::TogglePause => "Pause/resume",
+            HotkeyAction::AddMarker => "Drop marker",
+            HotkeyAction::ToggleWebcam => "Toggle webcam",
+        }
+    }
+}
+
+/// Hotkey bindings as stored in the app settings. An empty string disables
+/// the binding.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HotkeyBindings {
+    pub start_recording: String,
+    pub stop_recording: String,
+    pub toggle_pause: String,
+    pub add_marker: String,
+    pub toggle_webcam: String,
+}
+
+// The input hook doesn't swallow keystrokes, so the defaults avoid chords
+// the app being recorded would act on too.
+impl Default for HotkeyBindings {
+    fn default() -> Self {
+        HotkeyBindings {
+            start_recording: "Ctrl+Alt+R".to_string(),
+            stop_recording: "Ctrl+Alt+S".to_string(),
+            toggle_pause: "Ctrl+Alt+P".to_string(),
+            add_marker: "Ctrl+Alt+M".to_string(),
+            toggle_webcam: "Ctrl+Alt+W".to_string(),
+        }
+    }
+}
+
+impl HotkeyBindings {
+    fn entries(&self) -> [(HotkeyAction, &str); 5] {
+        [
+            (HotkeyAction::StartRecording, &self.start_recording),
+            (HotkeyAction::StopRecording, &self.stop_recording),
+            (HotkeyAction::TogglePause, &self.toggle_pause),
+            (HotkeyAction::AddMarker, &self.add_marker),
+            (HotkeyAction::ToggleWebcam, &self.toggle_webcam),
+        ]
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+}
+
+impl Modifiers {
+    fn any(&self) -> bool {
+        self.ctrl || self.alt || self.shift || self.meta
+    }
+}
+
+/// A modifier combination plus one non-modifier key, e.g. `Ctrl+Shift+R`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chord {
+    pub modifiers: Modifiers,
+    pub key: Key,
+}
+
+// Combinations the OS or nearly every application already relies on. The
+// hook doesn't swallow the keystroke, so these would also close a window,
+// reload a page or open a dialog in whatever is being recorded.
+const RESERVED_CHORDS: &[&str] = &[
+    "Alt+F4",
+    "Alt+Tab",
+    "Ctrl+Alt+Delete",
+    "Ctrl+A",
+    "Ctrl+C",
+    "Ctrl+V",
+    "Ctrl+X",
+    "Ctrl+Z",
+    "Ctrl+Y",
+    "Ctrl+S",
+    "Ctrl+Shift+P",
+    "Ctrl+Shift+R",
+    "Ctrl+Shift+S",
+    "Ctrl+Shift+W",
+    "Meta+L",
+    "Meta+D",
+];
+
+impl Chord {
+    pub fn parse(text: &str) -> Result<Chord, String> {
+        let mut modifiers = Modifiers::default();
+        let mut key = None;
+
+        for part in text.split('+').map(str::trim) {
+            match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "alt" | "option" => modifiers.alt = true,
+                "shift" => modifiers.shift = true,
+                "meta" | "super" | "win" | "cmd" | "command" => modifiers.meta = true,
+                "" => return Err(format!("Invalid hotkey \"{}\"", text)),
+                name => {
+                    if key.is_some() {
+                        return Err(format!(
+                            "Hotkey \"{}\" must contain exactly one non-modifier key",
+                            text
+                        ));
+                    }
+                    key = Some(parse_key(name).ok_or_else(|| {
+                        format!("Unknown key \"{}\" in hotkey \"{}\"", part, text)
+                    })?);
+                }
+            }
+        }
+
+        let key = key.ok_or_else(|| format!("Hotkey \"{}\" has no key", text))?;
+        Ok(Chord { modifiers, key })
+    }
+
+    /// Bare keys are only allowed for keys nobody types with (F-keys etc.),
+    /// otherwise a hotkey would fire on every normal keystroke.
+    fn is_usable_without_modifiers(&self) -> bool {
+        matches!(
+            self.key,
+            Key::F1
+                | Key::F2
+                | Key::F3
+                | Key::F4
+                | Key::F5
+                | Key::F6
+                | Key::F7
+                | Key::F8
+                | Key::F9
+                | Key::F10
+                | Key::F11
+                | Key::F12
+                | Key::PrintScreen
+                | Key::ScrollLock
+                | Key::Pause
+        )
+    }
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+    let key = match name {
+        "a" => Key::KeyA,
+        "b" => Key::KeyB,
+        "c" => Key::KeyC,
+        "d" => Key::KeyD,
+        "e" => Key::KeyE,
+        "f" => Key::KeyF,
+        "g" => Key::KeyG,
+        "h" => Key::KeyH,
+        "i" => Key::KeyI,
+        "j" => Key::KeyJ,
+        "k" => Key::KeyK,
+        "l" => Key::KeyL,
+        "m" => Key::KeyM,
+        "n" => Key::KeyN,
+        "o" => Key::KeyO,
+        "p" => Key::KeyP,
+        "q" => Key::KeyQ,
+        "r" => Key::KeyR,
+        "s" => Key::KeyS,
+        "t" => Key::KeyT,
+        "u" => Key::KeyU,
+        "v" => Key::KeyV,
+        "w" => Key::KeyW,
+        "x" => Key::KeyX,
+        "y" => Key::KeyY,
+        "z" => Key::KeyZ,
+        "0" => Key::Num0,
+        "1" => Key::Num1,
+        "2" => Key::Num2,
+        "3" => Key::Num3,
+        "4" => Key::Num4,
+        "5" => Key::Num5,
+        "6" => Key::Num6,
+        "7" => Key::Num7,
+        "8" => Key::Num8,
+        "9" => Key::Num9,
+        "f1" => Key::F1,
+        "f2" => Key::F2,
+        "f3" => Key::F3,
+        "f4" => Key::F4,
+        "f5" => Key::F5,
+        "f6" => Key::F6,
+        "f7" => Key::F7,
+        "f8" => Key::F8,
+        "f9" => Key::F9,
+        "f10" => Key::F10,
+        "f11" => Key::F11,
+        "f12" => Key::F12,
+        "space" => Key::Space,
+        "tab" => Key::Tab,
+        "enter" | "return" => Key::Return,
+        "escape" | "esc" => Key::Escape,
+        "backspace" => Key::Backspace,
+        "delete" | "del" => Key::Delete,
+        "insert" | "ins" => Key::Insert,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "up" => Key::UpArrow,
+        "down" => Key::DownArrow,
+        "left" => Key::LeftArrow,
+        "right" => Key::RightArrow,
+        "printscreen" => Key::PrintScreen,
+        "scrolllock" => Key::ScrollLock,
+        "pause" => Key::Pause,
+        "-" | "minus" => Key::Minus,
+        "=" | "equal" => Key::Equal,
+        "," | "comma" => Key::Comma,
+        "." | "period" => Key::Dot,
+        "/" | "slash" => Key::Slash,
+        ";" | "semicolon" => Key::SemiColon,
+        "'" | "quote" => Key::Quote,
+        "`" | "backquote" => Key::BackQuote,
+        "[" => Key::LeftBracket,
+        "]" => Key::RightBracket,
+        "\\" | "backslash" => Key::BackSlash,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Validated bindings, ready to be matched against keyboard events.
+pub struct HotkeyMap {
+    bindings: HotkeyBindings,
+    chords: Vec<(Chord, HotkeyAction)>,
+}
+
+impl HotkeyMap {
+    /// Parses every binding and rejects duplicates, reserved system shortcuts
+    /// and plain typing keys.
+    pub fn compile(bindings: HotkeyBindings) -> Result<HotkeyMap, String> {
+        let reserved: Vec<Chord> = RESERVED_CHORDS
+            .iter()
+            .filter_map(|c| Chord::parse(c).ok())
+            .collect();
+
+        let mut chords: Vec<(Chord, HotkeyAction)> = Vec::new();
+        for (action, text) in bindings.entries() {
+            if text.trim().is_empty() {
+                continue;
+            }
+            let chord = Chord::parse(text)?;
+
+            if !chord.modifiers.any() && !chord.is_usable_without_modifiers() {
+                return Err(format!(
+                    "Hotkey \"{}\" for {} needs at least one modifier (Ctrl, Alt, Shift or Meta)",
+                    text,
+                    action.label()
+                ));
+            }
+            if reserved.contains(&chord) {
+                return Err(format!(
+                    "Hotkey \"{}\" for {} conflicts with a system shortcut",
+                    text,
+                    action.label()
+                ));
+            }
+            if let Some((_, other)) = chords.iter().find(|(c, _)| *c == chord) {
+                return Err(format!(
+                    "Hotkey \"{}\" is assigned to both {} and {}",
+                    text,
+                    other.label(),
+                    action.label()
+                ));
+            }
+            chords.push((chord, action));
+        }
+
+        Ok(HotkeyMap { bindings, chords })
+    }
+
+    pub fn bindings(&self) -> &HotkeyBindings {
+        &self.bindings
+    }
+
+    pub fn lookup(&self, chord: &Chord) -> Option<HotkeyAction> {
+        self.chords
+            .iter()
+            .find(|(c, _)| c == chord)
+            .map(|(_, action)| *action)
+    }
+}
+
+impl Default for HotkeyMap {
+    fn default() -> Self {
+        HotkeyMap::compile(HotkeyBindings::default()).expect("default hotkeys are valid")
+    }
+}
+
+/// Tracks held modifier keys and repeats for the global input hook.
+#[derive(Default)]
+pub struct KeyTracker {
+    ctrl: [bool; 2],
+    alt: bool,
+    /// Right Alt on layouts where it types characters. Windows also reports
+    /// a Ctrl press along with it.
+    alt_gr: bool,
+    shift: [bool; 2],
+    meta: [bool; 2],
+    held: Vec<Key>,
+}
+
+impl KeyTracker {
+    /// Feeds a key press and returns the completed chord, if any. Modifier
+    /// presses and OS auto-repeat of a held key never produce a chord.
+    pub fn press(&mut self, key: Key) -> Option<Chord> {
+        if self.set_modifier(key, true) {
+            return None;
+        }
+        if self.held.contains(&key) {
+            return None;
+        }
+        self.held.push(key);
+        let modifiers = self.modifiers();
+        // AltGr+Q types "@" on a German layout; it must not count as Ctrl+Alt+Q.
+        if self.alt_gr && modifiers.ctrl {
+            return None;
+        }
+        Some(Chord { modifiers, key })
+    }
+
+    pub fn release(&mut self, key: Key) {
+        if !self.set_modifier(key, false) {
+            self.held.retain(|k| *k != key);
+        }
+    }
+
+    fn set_modifier(&mut self, key: Key, down: bool) -> bool {
+        let slot = match key {
+            Key::ControlLeft => &mut self.ctrl[0],
+            Key::ControlRight => &mut self.ctrl[1],
+            Key::Alt => &mut self.alt,
+            Key::AltGr => &mut self.alt_gr,
+            Key::ShiftLeft => &mut self.shift[0],
+            Key::ShiftRight => &mut self.shift[1],
+            Key::MetaLeft => &mut self.meta[0],
+            Key::MetaRight => &mut self.meta[1],
+            _ => return false,
+        };
+        *slot = down;
+        true
+    }
+
+    fn modifiers(&self) -> Modifiers {
+        Modifiers {
+            ctrl: self.ctrl.iter().any(|d| *d),
+            alt: self.alt || self.alt_gr,
+            shift: self.shift.iter().any(|d| *d),
+            meta: self.meta.iter().any(|d| *d),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings(start_recording: &str, stop_recording: &str) -> HotkeyBindings {
+        HotkeyBindings {
+            start_recording: start_recording.into(),
+            stop_recording: stop_recording.into(),
+            ..HotkeyBindings::default()
+        }
+    }
+
+    #[test]
+    fn parses_chords_in_any_case_and_order() {
+        let chord = Chord::parse("shift + CTRL + r").unwrap();
+        assert_eq!(chord.key, Key::KeyR);
+        assert_eq!(
+            chord.modifiers,
+            Modifiers {
+                ctrl: true,
+                shift: true,
+                ..Modifiers::default()
+            }
+        );
+        assert_eq!(
+            Chord::parse("Cmd+Option+F5").unwrap(),
+            Chord::parse("Meta+Alt+F5").unwrap()
+        );
+        assert_eq!(Chord::parse("Ctrl+Alt+Esc").unwrap().key, Key::Escape);
+    }
+
+    #[test]
+    fn rejects_malformed_chords() {
+        for text in [
+            "",
+            "Ctrl+",
+            "Ctrl+Shift",
+            "Ctrl+A+B",
+            "Ctrl+Nope",
+            "Ctrl++R",
+        ] {
+            assert!(Chord::parse(text).is_err(), "accepted {:?}", text);
+        }
+    }
+
+    #[test]
+    fn default_bindings_compile() {
+        let map = HotkeyMap::compile(HotkeyBindings::default()).unwrap();
+        assert_eq!(
+            map.lookup(&Chord::parse("Ctrl+Alt+R").unwrap()),
+            Some(HotkeyAction::StartRecording)
+        );
+        assert_eq!(map.lookup(&Chord::parse("Ctrl+Shift+R").unwrap()), None);
+    }
+
+    #[test]
+    fn rejects_conflicting_bindings() {
+        let err = HotkeyMap::compile(bindings("Ctrl+Alt+R", "Alt+Ctrl+r"))
+            .err()
+            .unwrap();
+        assert!(err.contains("assigned to both"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_reserved_chords() {
+        for chord in [
+            "Ctrl+C",
+            "Alt+F4",
+            "Ctrl+Shift+W",
+            "Ctrl+Shift+P",
+            "Ctrl+Shift+R",
+            "Ctrl+Shift+S",
+        ] {
+            let err = HotkeyMap::compile(bindings(chord, "")).err().unwrap();
+            assert!(err.contains("system shortcut"), "{}: {}", chord, err);
+        }
+    }
+
+    #[test]
+    fn requires_modifiers_except_for_function_keys() {
+        let err = HotkeyMap::compile(bindings("R", "")).err().unwrap();
+        assert!(err.contains("needs at least one modifier"), "{}", err);
+        assert!(HotkeyMap::compile(bindings("F9", "Pause")).is_ok());
+    }
+
+    #[test]
+    fn empty_bindings_are_disabled() {
+        let map = HotkeyMap::compile(bindings("", "  ")).unwrap();
+        assert_eq!(map.lookup(&Chord::parse("Ctrl+Alt+S").unwrap()), None);
+    }
+
+    #[test]
+    fn tracker_ignores_auto_repeat_until_release() {
+        let mut keys = KeyTracker::default();
+        assert_eq!(keys.press(Key::ControlLeft), None);
+        assert_eq!(keys.press(Key::Alt), None);
+        let chord = keys.press(Key::KeyR).unwrap();
+        assert_eq!(chord, Chord::parse("Ctrl+Alt+R").unwrap());
+        // Holding the key makes the OS repeat the press.
+        assert_eq!(keys.press(Key::KeyR), None);
+        assert_eq!(keys.press(Key::KeyR), None);
+        keys.release(Key::KeyR);
+        assert_eq!(keys.press(Key::KeyR), Some(chord));
+    }
+
+    #[test]
+    fn tracker_ignores_altgr_characters() {
+        let mut keys = KeyTracker::default();
+        // Windows sends a left Ctrl press ahead of AltGr.
+        keys.press(Key::ControlLeft);
+        keys.press(Key::AltGr);
+        assert_eq!(keys.press(Key::KeyR), None);
+        assert_eq!(keys.press(Key::KeyS), None);
+        keys.release(Key::KeyR);
+        keys.release(Key::KeyS);
+        keys.release(Key::AltGr);
+        keys.release(Key::ControlLeft);
+
+        // Left Alt still makes a Ctrl+Alt chord, and AltGr alone counts as Alt.
+        keys.press(Key::ControlLeft);
+        keys.press(Key::Alt);
+        assert_eq!(keys.press(Key::KeyR), Chord::parse("Ctrl+Alt+R").ok());
+        keys.release(Key::ControlLeft);
+        keys.release(Key::Alt);
+        keys.press(Key::AltGr);
+        assert_eq!(keys.press(Key::F5), Chord::parse("Alt+F5").ok());
+    }
+
+    #[test]
+    fn tracker_follows_modifier_releases() {
+        let mut keys = KeyTracker::default();
+        keys.press(Key::ShiftLeft);
+        keys.press(Key::ShiftRight);
+        keys.release(Key::ShiftLeft);
+        // The right Shift is still down.
+        assert_eq!(keys.press(Key::KeyM), Chord::parse("Shift+M").ok());
+        keys.release(Key::KeyM);
+        keys.release(Key::ShiftRight);
+        assert_eq!(keys.press(Key::KeyM), Chord::parse("M").ok());
+    }
+}
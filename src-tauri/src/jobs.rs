@@ -0,0 +1,543 @@
+use crate::edit::KeepRange;
+use crate::exports::{self, ExportPreset};
+use crate::{thumbnails, transcribe};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use tauri::{AppHandle, Manager};
+use tauri_specta::Event;
+
+/// Jobs that run at the same time; FFmpeg already spreads each over cores.
+pub const MAX_WORKERS: usize = 2;
+const JOBS_FILE: &str = "jobs.json";
+/// Finished jobs kept around for `list_jobs`.
+const HISTORY_LIMIT: usize = 50;
+
+/// Post-processing work that runs off the command thread.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum JobKind {
+    #[serde(rename_all = "camelCase")]
+    Export {
+        session_path: String,
+        preset: ExportPreset,
+    },
+    #[serde(rename_all = "camelCase")]
+    Thumbnails { session_path: String },
+    #[serde(rename_all = "camelCase")]
+    Transcribe {
+        session_path: String,
+        model_path: String,
+        language: Option<String>,
+        mux_subtitles: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    Trim {
+        session_path: String,
+        ranges: Vec<KeepRange>,
+        precise: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    DetectSilence {
+        session_path: String,
+        threshold_db: f64,
+        min_duration_ms: u64,
+    },
+    /// Cuts silent stretches out, or plays them at `silent_speed`.
+    #[serde(rename_all = "camelCase")]
+    RemoveSilence {
+        session_path: String,
+        threshold_db: f64,
+        min_duration_ms: u64,
+        silent_speed: Option<f64>,
+    },
+}
+
+impl JobKind {
+    pub fn session_path(&self) -> &str {
+        match self {
+            JobKind::Export { session_path, .. }
+            | JobKind::Thumbnails { session_path }
+            | JobKind::Transcribe { session_path, .. }
+            | JobKind::Trim { session_path, .. }
+            | JobKind::DetectSilence { session_path, .. }
+            | JobKind::RemoveSilence { session_path, .. } => session_path,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobStatus::Done | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    pub id: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    /// 0.0-1.0 while running.
+    pub progress: f64,
+    pub output: Option<String>,
+    pub error: Option<String>,
+    pub attempts: u32,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// Sent whenever a job is queued, makes progress or finishes.
+#[derive(Clone, Serialize, Deserialize, Type, tauri_specta::Event)]
+pub struct JobProgress(pub Job);
+
+#[derive(Default)]
+struct Queue {
+    jobs: Vec<Job>,
+    cancel_flags: HashMap<String, Arc<AtomicBool>>,
+}
+
+/// What `jobs.json` holds. Older versions stored only the job list.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Stored {
+    #[serde(rename_all = "camelCase")]
+    Current {
+        next_id: u64,
+        jobs: Vec<Job>,
+    },
+    Legacy(Vec<Job>),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredRef<'a> {
+    next_id: u64,
+    jobs: &'a [Job],
+}
+
+/// Bounded worker pool with a queue that is persisted to the app data dir,
+/// so pending work survives a restart.
+pub struct JobManager {
+    queue: Mutex<Queue>,
+    /// Never reused, even after finished jobs are pruned from the history.
+    next_id: AtomicU64,
+    wake: Condvar,
+    store_path: PathBuf,
+}
+
+fn now_millis() -> u64 {
+    chrono::Local::now().timestamp_millis() as u64
+}
+
+impl JobManager {
+    /// Loads the persisted queue. Jobs that were running when the app quit
+    /// are queued again.
+    pub fn load(data_dir: &Path) -> Self {
+        let store_path = data_dir.join(JOBS_FILE);
+        let (next_id, mut jobs) = match fs::read_to_string(&store_path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+        {
+            Some(Stored::Current { next_id, jobs }) => (next_id, jobs),
+            // Legacy ids embed a timestamp, so plain counters can't clash.
+            Some(Stored::Legacy(jobs)) => (0, jobs),
+            None => (0, Vec::new()),
+        };
+        for job in jobs.iter_mut().filter(|j| j.status == JobStatus::Running) {
+            job.status = JobStatus::Queued;
+            job.progress = 0.0;
+        }
+        let manager = JobManager {
+            queue: Mutex::new(Queue {
+                jobs,
+                cancel_flags: HashMap::new(),
+            }),
+            next_id: AtomicU64::new(next_id),
+            wake: Condvar::new(),
+            store_path,
+        };
+        manager.persist(&manager.queue.lock().unwrap());
+        manager
+    }
+
+    pub fn start_workers(app: &AppHandle) {
+        for _ in 0..MAX_WORKERS {
+            let app = app.clone();
+            std::thread::spawn(move || worker(app));
+        }
+    }
+
+    pub fn list(&self) -> Vec<Job> {
+        self.queue.lock().unwrap().jobs.clone()
+    }
+
+    /// Session folders with queued or running jobs.
+    pub fn busy_sessions(&self) -> Vec<PathBuf> {
+        self.queue
+            .lock()
+            .unwrap()
+            .jobs
+            .iter()
+            .filter(|j| !j.status.is_finished())
+            .map(|j| PathBuf::from(j.kind.session_path()))
+            .collect()
+    }
+
+    /// Queues `kind`, or returns the pending job that already does the same.
+    pub fn enqueue(&self, app: &AppHandle, kind: JobKind) -> Job {
+        let mut queue = self.queue.lock().unwrap();
+        if let Some(existing) = queue
+            .jobs
+            .iter()
+            .find(|j| j.kind == kind && !j.status.is_finished())
+        {
+            return existing.clone();
+        }
+
+        let now = now_millis();
+        let job = Job {
+            id: format!("job-{}", self.next_id.fetch_add(1, Ordering::SeqCst)),
+            kind,
+            status: JobStatus::Queued,
+            progress: 0.0,
+            output: None,
+            error: None,
+            attempts: 0,
+            created_at: now,
+            updated_at: now,
+        };
+        queue.jobs.push(job.clone());
+        prune_history(&mut queue.jobs);
+        self.persist(&queue);
+        drop(queue);
+
+        self.wake.notify_one();
+        let _ = JobProgress(job.clone()).emit(app);
+        job
+    }
+
+    /// Like `enqueue`, but leaves work that already failed alone until the
+    /// user retries it, for callers that would otherwise queue it repeatedly.
+    pub fn enqueue_unless_failed(&self, app: &AppHandle, kind: JobKind) -> Option<Job> {
+        let failed = self
+            .queue
+            .lock()
+            .unwrap()
+            .jobs
+            .iter()
+            .any(|j| j.kind == kind && j.status == JobStatus::Failed);
+        (!failed).then(|| self.enqueue(app, kind))
+    }
+
+    pub fn cancel(&self, app: &AppHandle, id: &str) -> Result<(), String> {
+        let mut queue = self.queue.lock().unwrap();
+        if let Some(flag) = queue.cancel_flags.get(id) {
+            // The worker notices, kills FFmpeg and marks the job itself.
+            flag.store(true, Ordering::SeqCst);
+            return Ok(());
+        }
+        let job = queue
+            .jobs
+            .iter_mut()
+            .find(|j| j.id == id)
+            .ok_or("Job not found")?;
+        if job.status != JobStatus::Queued {
+            return Err("Job is not running".into());
+        }
+        job.status = JobStatus::Cancelled;
+        job.updated_at = now_millis();
+        let job = job.clone();
+        self.persist(&queue);
+        let _ = JobProgress(job.clone()).emit(app);
+        Ok(())
+    }
+
+    /// Queues a failed or cancelled job again.
+    pub fn retry(&self, app: &AppHandle, id: &str) -> Result<Job, String> {
+        let mut queue = self.queue.lock().unwrap();
+        let job = queue
+            .jobs
+            .iter_mut()
+            .find(|j| j.id == id)
+            .ok_or("Job not found")?;
+        if !matches!(job.status, JobStatus::Failed | JobStatus::Cancelled) {
+            return Err("Only failed or cancelled jobs can be retried".into());
+        }
+        job.status = JobStatus::Queued;
+        job.progress = 0.0;
+        job.error = None;
+        job.updated_at = now_millis();
+        let job = job.clone();
+        self.persist(&queue);
+        drop(queue);
+
+        self.wake.notify_one();
+        let _ = JobProgress(job.clone()).emit(app);
+        Ok(job)
+    }
+
+    // Blocks until a queued job is available and claims it.
+    fn claim(&self, app: &AppHandle) -> (Job, Arc<AtomicBool>) {
+        let mut queue = self.queue.lock().unwrap();
+        loop {
+            if let Some(job) = queue
+                .jobs
+                .iter_mut()
+                .find(|j| j.status == JobStatus::Queued)
+            {
+                job.status = JobStatus::Running;
+                job.attempts += 1;
+                job.updated_at = now_millis();
+                let job = job.clone();
+                let cancel = Arc::new(AtomicBool::new(false));
+                queue.cancel_flags.insert(job.id.clone(), cancel.clone());
+                self.persist(&queue);
+                let _ = JobProgress(job.clone()).emit(app);
+                return (job, cancel);
+            }
+            queue = self.wake.wait(queue).unwrap();
+        }
+    }
+
+    fn report_progress(&self, app: &AppHandle, id: &str, progress: f64) {
+        let mut queue = self.queue.lock().unwrap();
+        if let Some(job) = queue.jobs.iter_mut().find(|j| j.id == id) {
+            job.progress = progress;
+            let _ = JobProgress(job.clone()).emit(app);
+        }
+    }
+
+    fn finish(&self, app: &AppHandle, id: &str, result: Result<Option<String>, String>) {
+        let mut queue = self.queue.lock().unwrap();
+        let cancelled = queue
+            .cancel_flags
+            .remove(id)
+            .is_some_and(|flag| flag.load(Ordering::SeqCst));
+        let Some(job) = queue.jobs.iter_mut().find(|j| j.id == id) else {
+            return;
+        };
+        match result {
+            _ if cancelled => job.status = JobStatus::Cancelled,
+            Ok(output) => {
+                job.status = JobStatus::Done;
+                job.progress = 1.0;
+                job.output = output;
+            }
+            Err(e) => {
+                println!("Job {} failed: {}", id, e);
+                job.status = JobStatus::Failed;
+                job.error = Some(e);
+            }
+        }
+        job.updated_at = now_millis();
+        let job = job.clone();
+        self.persist(&queue);
+        let _ = JobProgress(job.clone()).emit(app);
+    }
+
+    fn persist(&self, queue: &Queue) {
+        let write = || -> Result<(), String> {
+            if let Some(dir) = self.store_path.parent() {
+                fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            let stored = StoredRef {
+                next_id: self.next_id.load(Ordering::SeqCst),
+                jobs: &queue.jobs,
+            };
+            let json = serde_json::to_string_pretty(&stored).map_err(|e| e.to_string())?;
+            let temp_path = self.store_path.with_extension("json.tmp");
+            fs::write(&temp_path, json).map_err(|e| e.to_string())?;
+            fs::rename(&temp_path, &self.store_path).map_err(|e| e.to_string())
+        };
+        if let Err(e) = write() {
+            println!("Failed to save job queue: {}", e);
+        }
+    }
+}
+
+// Drops the oldest finished jobs beyond the history limit; pending ones stay.
+fn prune_history(jobs: &mut Vec<Job>) {
+    let finished = jobs.iter().filter(|j| j.status.is_finished()).count();
+    let mut excess = finished.saturating_sub(HISTORY_LIMIT);
+    jobs.retain(|j| {
+        if excess > 0 && j.status.is_finished() {
+            excess -= 1;
+            false
+        } else {
+            true
+        }
+    });
+}
+
+fn worker(app: AppHandle) {
+    let manager = app.state::<JobManager>();
+    loop {
+        let (job, cancel) = manager.claim(&app);
+        let mut last_percent = None;
+        let result = execute(&job.kind, &cancel, &mut |fraction| {
+            // One event per whole percent is plenty for a progress bar.
+            let percent = (fraction * 100.0) as u32;
+            if last_percent != Some(percent) {
+                last_percent = Some(percent);
+                manager.report_progress(&app, &job.id, fraction);
+            }
+        });
+        manager.finish(&app, &job.id, result);
+    }
+}
+
+fn execute(
+    kind: &JobKind,
+    cancel: &Arc<AtomicBool>,
+    on_progress: &mut (dyn FnMut(f64) + Send),
+) -> Result<Option<String>, String> {
+    match kind {
+        JobKind::Export {
+            session_path,
+            preset,
+        } => {
+            let session_dir = PathBuf::from(session_path);
+            let video_path =
+                crate::segments::single_file(&session_dir).ok_or("Recording not found")?;
+            let exports_dir = session_dir.join(exports::EXPORTS_DIR);
+            fs::create_dir_all(&exports_dir)
+                .map_err(|e| format!("Failed to create exports folder: {}", e))?;
+            let target = exports_dir.join(format!(
+                "screen_{}_{}.{}",
+                preset.slug(),
+                chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"),
+                preset.extension()
+            ));
+
+            let session_manifest = crate::load_session_manifest(&session_dir, &video_path);
+            let has_audio = session_manifest
+                .media
+                .as_ref()
+                .is_none_or(|m| m.audio_tracks().next().is_some());
+            println!("Exporting {} as {:?}", video_path.display(), preset);
+            exports::run_export(
+                &video_path,
+                &target,
+                *preset,
+                has_audio,
+                session_manifest.duration_ms,
+                cancel,
+                on_progress,
+            )?;
+            Ok(Some(target.to_string_lossy().to_string()))
+        }
+        JobKind::Thumbnails { session_path } => {
+            let session_dir = PathBuf::from(session_path);
+            let video_path =
+                crate::segments::video_path(&session_dir).ok_or("Recording not found")?;
+            let session_manifest = crate::load_session_manifest(&session_dir, &video_path);
+            // Previews of a split recording come from its first part.
+            let duration_ms = session_manifest
+                .media
+                .as_ref()
+                .map(|m| m.duration_ms)
+                .filter(|ms| *ms > 0)
+                .unwrap_or(session_manifest.duration_ms);
+            let previews =
+                thumbnails::ensure_previews(&video_path, duration_ms, cancel, on_progress);
+            if cancel.load(Ordering::SeqCst) {
+                return Err(crate::CANCELLED.to_string());
+            }
+            if previews.sprite.is_none() && duration_ms > 0 {
+                return Err("Failed to generate preview strip".into());
+            }
+            previews
+                .poster
+                .map(|p| Some(p.to_string_lossy().to_string()))
+                .ok_or_else(|| "Failed to generate thumbnail".to_string())
+        }
+        JobKind::Transcribe {
+            session_path,
+            model_path,
+            language,
+            mux_subtitles,
+        } => {
+            let session_dir = PathBuf::from(session_path);
+            let video_path =
+                crate::segments::single_file(&session_dir).ok_or("Recording not found")?;
+            println!("Transcribing {} with {}", video_path.display(), model_path);
+            let transcript = transcribe::transcribe(
+                &video_path,
+                Path::new(model_path),
+                language.as_deref(),
+                cancel,
+                on_progress,
+            )?;
+            transcribe::write_outputs(&session_dir, &transcript)?;
+
+            let srt_path = session_dir.join(transcribe::SRT_FILE);
+            if *mux_subtitles && !transcript.segments.is_empty() {
+                transcribe::mux_subtitles(&video_path, &srt_path, &transcript.language)?;
+            }
+            Ok(Some(srt_path.to_string_lossy().to_string()))
+        }
+        JobKind::Trim {
+            session_path,
+            ranges,
+            precise,
+        } => {
+            let target = crate::trim_session(
+                Path::new(session_path),
+                ranges,
+                *precise,
+                cancel,
+                on_progress,
+            )?;
+            Ok(Some(target.to_string_lossy().to_string()))
+        }
+        JobKind::DetectSilence {
+            session_path,
+            threshold_db,
+            min_duration_ms,
+        } => {
+            crate::detect_session_silence(
+                Path::new(session_path),
+                *threshold_db,
+                *min_duration_ms,
+                cancel,
+                on_progress,
+            )?;
+            Ok(None)
+        }
+        JobKind::RemoveSilence {
+            session_path,
+            threshold_db,
+            min_duration_ms,
+            silent_speed,
+        } => {
+            let target = crate::remove_session_silence(
+                Path::new(session_path),
+                *threshold_db,
+                *min_duration_ms,
+                *silent_speed,
+                cancel,
+                on_progress,
+            )?;
+            Ok(Some(target.to_string_lossy().to_string()))
+        }
+    }
+}
@@ -1,38 +1,250 @@
-use rdev::{listen, Button, EventType, Key};
+mod bindings;
+mod chapters;
+mod disk;
+mod edit;
+mod encoding;
+mod exports;
+mod guard;
+mod hotkeys;
+mod jobs;
+mod library;
+mod manifest;
+mod media;
+mod profiles;
+mod replay;
+mod retention;
+mod scaling;
+mod search;
+mod segments;
+mod settings;
+mod silence;
+mod thumbnails;
+mod transcribe;
+mod trash;
+mod watcher;
+
+use chapters::Marker;
+use edit::{EditRecord, KeepRange};
+use encoding::{Container, VideoEncoder};
+use exports::ExportPreset;
+use hotkeys::{HotkeyAction, HotkeyBindings, HotkeyMap, KeyTracker};
+use jobs::{Job, JobKind, JobManager};
+use library::{LibraryCache, ListQuery, RecordingPage};
+use manifest::SessionManifest;
+use profiles::{ProfileStore, RecordingProfile};
+use rdev::{listen, Button, EventType};
 use regex::Regex;
+use replay::ReplayBuffer;
+use retention::{RetentionCandidate, RetentionPolicy};
+use scaling::ScaleMode;
 use serde::{Deserialize, Serialize};
+use settings::{AppSettings, SettingsChanged, SettingsStore};
+use silence::SilenceAnalysis;
+use specta::Type;
 use std::fs;
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
+use std::time::Instant;
 use tauri::Manager;
-use tauri::{Emitter, State}; // Added Emitter
-use tauri_plugin_shell::process::CommandEvent;
-use tauri_plugin_shell::ShellExt;
+use tauri::{AppHandle, State};
+use tauri_specta::Event;
+use watcher::LibraryWatcher;
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, Deserialize, Type)]
 struct ClickPayload {
     x: f64,
     y: f64,
     button: String,
 }
 
+/// A mouse click anywhere on screen, for the overlay's ripples.
+#[derive(Clone, Serialize, Deserialize, Type, tauri_specta::Event)]
+struct GlobalClick(ClickPayload);
+
+/// A key press anywhere, named as `rdev` debug-prints it, e.g. "KeyA".
+#[derive(Clone, Serialize, Deserialize, Type, tauri_specta::Event)]
+struct GlobalKey(String);
+
+#[derive(Clone, Serialize, Deserialize, Type, tauri_specta::Event)]
+struct MarkerAdded(Marker);
+
+#[derive(Clone, Serialize, Deserialize, Type, tauri_specta::Event)]
+struct ReplayBufferChanged(ReplayStatus);
+
 struct RecordingState {
     process: Child,
     output_path: PathBuf,
+    started_at: Instant,
+    manifest: SessionManifest,
+}
+
+// Spawns a helper process without flashing a console window on Windows.
+pub(crate) fn background_command(program: &str) -> Command {
+    #[cfg_attr(not(windows), allow(unused_mut))]
+    let mut cmd = Command::new(program);
+    #[cfg(windows)]
+    {
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    cmd
+}
+
+/// Runs ffmpeg to completion, reporting the last line of its log on failure.
+pub(crate) fn run_ffmpeg<I: IntoIterator<Item = String>>(args: I) -> Result<(), String> {
+    let output = background_command("ffmpeg")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "ffmpeg failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .last()
+                .unwrap_or("")
+        ))
+    }
+}
+
+/// Returned by jobs stopped through their cancel flag.
+pub(crate) const CANCELLED: &str = "Cancelled";
+
+/// Like `run_ffmpeg`, reporting progress as a fraction of `duration_ms` and
+/// killing FFmpeg once `cancel` is set. `target` is removed unless it
+/// finishes.
+pub(crate) fn run_ffmpeg_with_progress(
+    args: Vec<String>,
+    target: &Path,
+    duration_ms: u64,
+    cancel: &AtomicBool,
+    on_progress: &mut dyn FnMut(f64),
+) -> Result<(), String> {
+    let mut child = background_command("ffmpeg")
+        .args(["-v", "error", "-nostats", "-progress", "pipe:1"])
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    // Drain stderr on its own thread so a chatty encoder can't fill the pipe
+    // and stall while we're reading progress from stdout.
+    let mut stderr = child.stderr.take().unwrap();
+    let stderr_reader = std::thread::spawn(move || {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text);
+        text
+    });
+
+    // `-progress` prints key=value blocks; out_time_us is the encoded position.
+    let stdout = child.stdout.take().unwrap();
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+        if cancel.load(Ordering::SeqCst) {
+            let _ = child.kill();
+            break;
+        }
+        let position_us = line
+            .strip_prefix("out_time_us=")
+            .or_else(|| line.strip_prefix("out_time_ms="))
+            .and_then(|v| v.trim().parse::<u64>().ok());
+        if let (Some(us), true) = (position_us, duration_ms > 0) {
+            on_progress((us as f64 / 1000.0 / duration_ms as f64).min(1.0));
+        }
+    }
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    let log = stderr_reader.join().unwrap_or_default();
+    if cancel.load(Ordering::SeqCst) {
+        let _ = fs::remove_file(target);
+        return Err(CANCELLED.to_string());
+    }
+    if !status.success() {
+        let _ = fs::remove_file(target);
+        return Err(format!(
+            "ffmpeg failed: {}",
+            log.lines().last().unwrap_or("")
+        ));
+    }
+    on_progress(1.0);
+    Ok(())
+}
+
+/// Total size of the files under `path`.
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .map(|dir| {
+            dir.flatten()
+                .map(|entry| match entry.file_type() {
+                    Ok(t) if t.is_dir() => dir_size(&entry.path()),
+                    _ => entry.metadata().map(|m| m.len()).unwrap_or(0),
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// The configured save folder, or `Videos/Reframe` when none is set.
+pub(crate) fn save_root(save_path: &str) -> PathBuf {
+    if !save_path.is_empty() {
+        PathBuf::from(save_path)
+    } else {
+        dirs::video_dir()
+            .unwrap_or(PathBuf::from("./"))
+            .join("Reframe")
+    }
 }
 
 struct AppState {
     recording: Mutex<Option<RecordingState>>,
+    /// Resolved save folder; every filesystem command is confined to it.
+    save_root: Mutex<PathBuf>,
     listener_running: Mutex<bool>,
+    hotkeys: Mutex<HotkeyMap>,
+    library: Mutex<LibraryCache>,
+    watcher: Mutex<Option<LibraryWatcher>>,
+    /// Locked after `recording` whenever both are needed.
+    replay: Mutex<Option<ReplayBuffer>>,
+}
+
+fn library_root(state: &AppState) -> PathBuf {
+    state.save_root.lock().unwrap().clone()
+}
+
+/// The folder of the recording `id` (its `Session_*` folder name).
+fn session_path(state: &AppState, id: &str) -> Result<PathBuf, String> {
+    guard::session_dir(&library_root(state), id)
+}
+
+// Switches the folder commands are confined to and lets the webview load
+// media from it.
+fn set_save_root(app: &AppHandle, state: &AppState, save_path: &str) {
+    let root = save_root(save_path);
+    if let Err(e) = fs::create_dir_all(&root) {
+        println!("Failed to create save folder: {}", e);
+    }
+    if let Err(e) = app.asset_protocol_scope().allow_directory(&root, true) {
+        println!("Failed to allow asset access to {}: {}", root.display(), e);
+    }
+    *state.save_root.lock().unwrap() = root;
 }
 
 #[tauri::command]
+#[specta::specta]
 fn start_global_listener(app: tauri::AppHandle, state: State<AppState>) {
+    spawn_global_listener(app, &state);
+}
+
+// The listener is started from setup so hotkeys work even before (or without)
+// the overlay window mounting; the command stays for the overlay's benefit.
+fn spawn_global_listener(app: AppHandle, state: &AppState) {
     let mut running = state.listener_running.lock().unwrap();
     if *running {
         println!("Global listener already running.");
@@ -44,6 +256,7 @@ fn start_global_listener(app: tauri::AppHandle, state: State<AppState>) {
     std::thread::spawn(move || {
         let mut last_x = 0.0;
         let mut last_y = 0.0;
+        let mut keys = KeyTracker::default();
 
         if let Err(error) = listen(move |event| match event.event_type {
             EventType::MouseMove { x, y } => {
@@ -51,28 +264,39 @@ fn start_global_listener(app: tauri::AppHandle, state: State<AppState>) {
                 last_y = y;
             }
             EventType::ButtonPress(Button::Left) => {
-                let _ = app.emit(
-                    "global-click",
-                    ClickPayload {
-                        x: last_x,
-                        y: last_y,
-                        button: "left".to_string(),
-                    },
-                );
+                let _ = GlobalClick(ClickPayload {
+                    x: last_x,
+                    y: last_y,
+                    button: "left".to_string(),
+                })
+                .emit(&app);
             }
             EventType::ButtonPress(Button::Right) => {
-                let _ = app.emit(
-                    "global-click",
-                    ClickPayload {
-                        x: last_x,
-                        y: last_y,
-                        button: "right".to_string(),
-                    },
-                );
+                let _ = GlobalClick(ClickPayload {
+                    x: last_x,
+                    y: last_y,
+                    button: "right".to_string(),
+                })
+                .emit(&app);
             }
             EventType::KeyPress(key) => {
-                let _ = app.emit("global-key", format!("{:?}", key));
+                let _ = GlobalKey(format!("{:?}", key)).emit(&app);
+
+                if let Some(chord) = keys.press(key) {
+                    let action = app
+                        .state::<AppState>()
+                        .hotkeys
+                        .lock()
+                        .unwrap()
+                        .lookup(&chord);
+                    if let Some(action) = action {
+                        // Never block the OS input hook; stopping can take seconds.
+                        let app = app.clone();
+                        std::thread::spawn(move || handle_hotkey(&app, action));
+                    }
+                }
             }
+            EventType::KeyRelease(key) => keys.release(key),
             _ => {}
         }) {
             println!("Error: {:?}", error);
@@ -80,21 +304,82 @@ fn start_global_listener(app: tauri::AppHandle, state: State<AppState>) {
     });
 }
 
-#[derive(Serialize)]
+fn handle_hotkey(app: &AppHandle, action: HotkeyAction) {
+    println!("Hotkey triggered: {:?}", action);
+    let state = app.state::<AppState>();
+    let result = match action {
+        HotkeyAction::StartRecording => {
+            let opts = recording_options_from_settings(app);
+            begin_recording(app, &state, opts, "hotkey").map(|_| ())
+        }
+        HotkeyAction::StopRecording => end_recording(app, &state, "hotkey").map(|_| ()),
+        HotkeyAction::AddMarker => add_marker_inner(app, &state, None).map(|_| ()),
+        // Pause and the webcam toggle are owned by the dashboard.
+        _ => action.emit(app).map_err(|e| e.to_string()),
+    };
+    if let Err(e) = result {
+        println!("Hotkey {:?} failed: {}", action, e);
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+fn get_hotkeys(state: State<AppState>) -> HotkeyBindings {
+    state.hotkeys.lock().unwrap().bindings().clone()
+}
+
+fn settings(app: &AppHandle) -> AppSettings {
+    app.state::<SettingsStore>().get()
+}
+
+#[tauri::command]
+#[specta::specta]
+fn get_settings(store: State<SettingsStore>) -> AppSettings {
+    store.get()
+}
+
+/// Applies `changes`, a subset of the settings keyed like `AppSettings`,
+/// and broadcasts the result to every window as `settings-changed`.
+#[tauri::command]
+#[specta::specta]
+fn update_settings(
+    app: AppHandle,
+    state: State<AppState>,
+    store: State<SettingsStore>,
+    changes: serde_json::Map<String, serde_json::Value>,
+) -> Result<AppSettings, String> {
+    let (previous, settings) = store.update(&changes)?;
+    if settings.hotkeys != previous.hotkeys {
+        *state.hotkeys.lock().unwrap() = HotkeyMap::compile(settings.hotkeys.clone())?;
+    }
+    if settings.save_path != previous.save_path {
+        set_save_root(&app, &state, &settings.save_path);
+    }
+    store.schedule_save(&app);
+    let _ = SettingsChanged(settings.clone()).emit(&app);
+    Ok(settings)
+}
+
+#[derive(Serialize, Type)]
 struct DeviceList {
     audio: Vec<String>,
     video: Vec<String>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Type)]
 #[serde(rename_all = "camelCase")]
-struct RecordingOptions {
+pub(crate) struct RecordingOptions {
+    #[serde(default)]
+    name: Option<String>,
     mic_enabled: bool,
     mic_device: Option<String>,
     system_audio_enabled: bool,
-    save_path: String,
+    /// Picks the default scale mode. Not wired into the FFmpeg input yet,
+    /// like the window title and region below.
     capture_mode: Option<String>,
+    #[allow(dead_code)]
     window_title: Option<String>,
+    #[allow(dead_code)]
     region: Option<String>,
     #[serde(rename = "micVolume")]
     mic_volume: Option<f32>,
@@ -103,25 +388,129 @@ struct RecordingOptions {
     framerate: Option<u32>,
     width: Option<u32>,
     height: Option<u32>,
+    /// How the capture is fitted into `width`x`height`; by default it
+    /// depends on the capture mode.
+    #[serde(default)]
+    scale_mode: Option<ScaleMode>,
+    /// Start a new part every this many minutes.
+    segment_minutes: Option<u32>,
+    /// Start a new part at roughly this size.
+    segment_size_mb: Option<u32>,
+    #[serde(default)]
+    encoder: VideoEncoder,
+    #[serde(default)]
+    container: Container,
+    /// Extra video filter chain applied after scaling.
+    #[serde(default)]
+    filters: Option<String>,
+}
+
+impl RecordingOptions {
+    /// Rejects values FFmpeg would fail on, or that make no sense to record.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if let Some(mode) = self.capture_mode.as_deref() {
+            if !["fullscreen", "window", "region"].contains(&mode) {
+                return Err(format!("Unknown capture mode: {}", mode));
+            }
+        }
+        if self.framerate.is_some_and(|fps| !(1..=240).contains(&fps)) {
+            return Err("Frame rate must be between 1 and 240".into());
+        }
+        match (self.width, self.height) {
+            (None, None) => {}
+            (Some(w), Some(h)) => {
+                if !(16..=7680).contains(&w) || !(16..=4320).contains(&h) {
+                    return Err(format!("Unsupported resolution {}x{}", w, h));
+                }
+            }
+            _ => return Err("Width and height must be set together".into()),
+        }
+        for volume in [self.mic_volume, self.system_audio_volume]
+            .into_iter()
+            .flatten()
+        {
+            if !(0.0..=10.0).contains(&volume) {
+                return Err("Volume must be between 0 and 10".into());
+            }
+        }
+        if let Some(filters) = &self.filters {
+            encoding::validate_filters(filters)?;
+        }
+        Ok(())
+    }
+}
+
+// Mirrors how the dashboard assembles options in `buildRecordingOptions`.
+fn recording_options_from_settings(app: &AppHandle) -> RecordingOptions {
+    let settings = settings(app);
+    let (width, height) = settings
+        .resolution_size()
+        .map_or((None, None), |(w, h)| (Some(w), Some(h)));
+
+    RecordingOptions {
+        name: None,
+        mic_enabled: settings.mic_enabled,
+        mic_device: Some(settings.mic_device),
+        system_audio_enabled: settings.system_audio_enabled,
+        capture_mode: Some(settings.capture_mode.as_str().to_string()),
+        window_title: None,
+        region: None,
+        mic_volume: Some(settings.mic_volume),
+        system_audio_volume: Some(settings.system_audio_volume),
+        framerate: Some(settings.fps),
+        width,
+        height,
+        scale_mode: settings.scale_mode,
+        segment_minutes: settings.segment_minutes,
+        segment_size_mb: settings.segment_size_mb,
+        encoder: settings.encoder,
+        container: settings.container,
+        filters: None,
+    }
+}
+
+// FFmpeg can't open "Default", so pick the first real microphone instead,
+// like the dashboard does.
+fn resolve_mic_device(opts: &mut RecordingOptions) -> Result<(), String> {
+    if opts.mic_enabled && opts.mic_device.as_deref().unwrap_or("Default") == "Default" {
+        if let Some(device) = probe_dshow_devices()?
+            .audio
+            .into_iter()
+            .find(|d| d != "Default")
+        {
+            opts.mic_device = Some(device);
+        }
+    }
+    Ok(())
 }
 
 // Redundant command removed as convertFileSrc is used in frontend.
 
+/// Opens the save folder, or the folder at/containing `path` inside it.
 #[tauri::command]
-fn open_folder(app: tauri::AppHandle, path: String) -> Result<(), String> {
+#[specta::specta]
+fn open_folder(
+    app: tauri::AppHandle,
+    state: State<AppState>,
+    path: Option<String>,
+) -> Result<(), String> {
     use tauri_plugin_opener::OpenerExt;
-    let target_path = if !path.is_empty() {
-        std::path::PathBuf::from(path)
-    } else {
-        dirs::video_dir()
-            .unwrap_or(std::path::PathBuf::from("./"))
-            .join("Reframe")
+    let root = library_root(&state);
+    let target_path = match path.filter(|p| !p.is_empty()) {
+        Some(path) => {
+            let path = guard::within(&root, Path::new(&path))?;
+            if path.is_file() {
+                path.parent().map(Path::to_path_buf).unwrap_or(root)
+            } else {
+                path
+            }
+        }
+        None => {
+            guard::canonical_root(&root)?;
+            root
+        }
     };
 
-    if !target_path.exists() {
-        std::fs::create_dir_all(&target_path).ok();
-    }
-
     let path_str = target_path.to_string_lossy().to_string();
     println!("Opening folder: {}", path_str);
     app.opener()
@@ -131,146 +520,767 @@ fn open_folder(app: tauri::AppHandle, path: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-fn open_file(app: tauri::AppHandle, path: String) -> Result<(), String> {
+#[specta::specta]
+fn open_file(app: tauri::AppHandle, state: State<AppState>, path: String) -> Result<(), String> {
     use tauri_plugin_opener::OpenerExt;
-    println!("Opening file: {}", path);
+    let path = guard::within(&library_root(&state), Path::new(&path))?;
+    println!("Opening file: {}", path.display());
     app.opener()
-        .open_path(path, None::<String>)
+        .open_path(path.to_string_lossy(), None::<String>)
         .map_err(|e| format!("Failed to open file: {}", e))?;
     Ok(())
 }
 
-#[derive(Serialize, Deserialize)]
-struct RecordingMetadata {
-    name: String,
-    duration: String,
-    timestamp: u64,
+#[tauri::command]
+#[specta::specta]
+fn add_marker(
+    app: AppHandle,
+    state: State<AppState>,
+    label: Option<String>,
+) -> Result<Marker, String> {
+    add_marker_inner(&app, &state, label)
 }
 
-#[tauri::command]
-fn save_metadata(path: String, metadata: String) -> Result<(), String> {
-    let metadata_path = std::path::Path::new(&path).join("metadata.json");
-    fs::write(metadata_path, metadata).map_err(|e| format!("Failed to save metadata: {}", e))?;
-    Ok(())
+fn add_marker_inner(
+    app: &AppHandle,
+    state: &AppState,
+    label: Option<String>,
+) -> Result<Marker, String> {
+    let mut recording = state.recording.lock().unwrap();
+    let rec_state = recording.as_mut().ok_or("Not recording")?;
+
+    let markers = &mut rec_state.manifest.markers;
+    let marker = Marker {
+        time_ms: rec_state.started_at.elapsed().as_millis() as u64,
+        label: label
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .unwrap_or_else(|| format!("Chapter {}", markers.len() + 1)),
+    };
+    markers.push(marker.clone());
+
+    // Persist immediately so markers survive a crash mid-recording.
+    if let Some(session_dir) = rec_state.output_path.parent() {
+        manifest::save(session_dir, &rec_state.manifest)?;
+    }
+
+    println!("Marker added at {} ms: {}", marker.time_ms, marker.label);
+    let _ = MarkerAdded(marker.clone()).emit(app);
+    Ok(marker)
 }
 
-#[derive(Serialize)]
-struct FileRecord {
-    id: u64,
-    name: String,
-    duration: String,
-    size: String,
-    folder: String,
-    files: Vec<String>,
-    #[serde(rename = "fullPath")]
-    full_path: String,
+pub(crate) fn created_millis(path: &std::path::Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.created())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// Loads the session manifest (creating one for bare folders) and re-probes
+// the video whenever it changed since the cached media info was taken.
+pub(crate) fn load_session_manifest(
+    session_dir: &std::path::Path,
+    video_path: &std::path::Path,
+) -> SessionManifest {
+    let (mut session_manifest, persist) = match manifest::load(session_dir) {
+        Ok(Some(m)) => (m, true),
+        result => {
+            if let Err(e) = &result {
+                println!("Ignoring manifest of {}: {}", session_dir.display(), e);
+            }
+            let name = session_dir
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string();
+            let video_file = video_path
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let mut m = SessionManifest::new(name, video_file, None);
+            m.app_version = String::new();
+            m.created_at = created_millis(session_dir);
+            // Never overwrite a manifest we failed to read.
+            (m, result.is_ok())
+        }
+    };
+
+    let is_current = session_manifest
+        .media
+        .as_ref()
+        .is_some_and(|m| m.is_current(video_path));
+    if !is_current {
+        match media::probe(video_path) {
+            Ok(info) => {
+                // A split recording's duration covers all of its parts.
+                if info.duration_ms > 0 && session_manifest.parts.is_empty() {
+                    session_manifest.duration_ms = info.duration_ms;
+                }
+                session_manifest.media = Some(info);
+                if persist {
+                    if let Err(e) = manifest::save(session_dir, &session_manifest) {
+                        println!("Failed to cache media info: {}", e);
+                    }
+                }
+            }
+            Err(e) => println!("Failed to probe {}: {}", video_path.display(), e),
+        }
+    }
+
+    session_manifest
 }
 
 #[tauri::command]
-async fn list_recordings(save_path: String) -> Result<Vec<FileRecord>, String> {
-    let root_dir = if !save_path.is_empty() {
-        PathBuf::from(&save_path)
-    } else {
-        dirs::video_dir()
-            .unwrap_or(PathBuf::from("./"))
-            .join("Reframe")
+#[specta::specta]
+async fn list_recordings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    query: Option<ListQuery>,
+) -> Result<RecordingPage, String> {
+    let root = library_root(&state);
+    library::list(&state.library, &app, &root, &query.unwrap_or_default())
+}
+
+/// Queues a trim of the recording down to `ranges`; the job's output is the
+/// trimmed file.
+#[tauri::command]
+#[specta::specta]
+fn trim_recording(
+    app: AppHandle,
+    state: State<AppState>,
+    jobs: State<JobManager>,
+    id: String,
+    ranges: Vec<KeepRange>,
+    precise: Option<bool>,
+) -> Result<Job, String> {
+    let session_dir = session_path(&state, &id)?;
+    if segments::single_file(&session_dir).is_none() {
+        return Err("Recording not found".into());
+    }
+    // Clamped to the real duration once the job runs.
+    edit::normalize_ranges(&ranges, 0)?;
+    Ok(jobs.enqueue(
+        &app,
+        JobKind::Trim {
+            session_path: session_dir.to_string_lossy().to_string(),
+            ranges,
+            precise: precise.unwrap_or(false),
+        },
+    ))
+}
+
+/// Runs a `Trim` job and returns the trimmed file.
+pub(crate) fn trim_session(
+    session_dir: &Path,
+    ranges: &[KeepRange],
+    precise: bool,
+    cancel: &AtomicBool,
+    on_progress: &mut dyn FnMut(f64),
+) -> Result<PathBuf, String> {
+    let video_path = segments::single_file(session_dir).ok_or("Recording not found")?;
+
+    let session_manifest = load_session_manifest(session_dir, &video_path);
+    let ranges = edit::normalize_ranges(ranges, session_manifest.duration_ms)?;
+    let has_audio = session_manifest
+        .media
+        .as_ref()
+        .is_none_or(|m| m.audio_tracks().next().is_some());
+    let file = edit::claim_output(session_dir, "screen_trim")?;
+    let target = session_dir.join(&file);
+    println!(
+        "Trimming {} to {} range(s), precise: {}",
+        video_path.display(),
+        ranges.len(),
+        precise
+    );
+    if let Err(e) = edit::trim(
+        &video_path,
+        &ranges,
+        precise,
+        has_audio,
+        &session_manifest.markers,
+        &target,
+        cancel,
+        on_progress,
+    ) {
+        let _ = fs::remove_file(&target);
+        return Err(e);
+    }
+
+    let duration_ms = media::probe(&target)
+        .map(|info| info.duration_ms)
+        .unwrap_or_else(|_| ranges.iter().map(|r| r.end_ms - r.start_ms).sum());
+    let edit = EditRecord {
+        kind: "trim".to_string(),
+        file,
+        ranges,
+        precise,
+        duration_ms,
+        created_at: chrono::Local::now().timestamp_millis() as u64,
     };
+    // Reloaded, so renames and tags made while trimming are kept.
+    manifest::update(
+        session_dir,
+        || bare_manifest(session_dir),
+        |manifest| manifest.edits.push(edit),
+    )?;
+
+    Ok(target)
+}
 
-    if !root_dir.exists() {
-        fs::create_dir_all(&root_dir).ok();
-        return Ok(Vec::new());
+// Reuses the cached analysis when it was run with the same parameters and the
+// video hasn't changed since.
+fn silence_analysis(
+    session_dir: &Path,
+    video_path: &Path,
+    session_manifest: &SessionManifest,
+    threshold_db: f64,
+    min_duration_ms: u64,
+    cancel: &AtomicBool,
+    on_progress: &mut dyn FnMut(f64),
+) -> Result<SilenceAnalysis, String> {
+    let media = session_manifest.media.clone().unwrap_or_default();
+    if let Some(cached) = &session_manifest.silence {
+        if cached.matches(threshold_db, min_duration_ms) && media.is_current(video_path) {
+            return Ok(cached.clone());
+        }
     }
 
-    let mut recordings = Vec::new();
-    let entries = fs::read_dir(root_dir).map_err(|e| e.to_string())?;
+    println!(
+        "Detecting silence in {} (threshold {} dB, min {} ms)",
+        video_path.display(),
+        threshold_db,
+        min_duration_ms
+    );
+    let analysis = silence::analyze(
+        video_path,
+        media.audio_tracks().count(),
+        threshold_db,
+        min_duration_ms,
+        session_manifest.duration_ms,
+        cancel,
+        on_progress,
+    )?;
+    let cached = analysis.clone();
+    manifest::update(
+        session_dir,
+        || bare_manifest(session_dir),
+        |manifest| manifest.silence = Some(cached),
+    )?;
+    Ok(analysis)
+}
 
-    for entry in entries {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            if path.is_dir()
-                && path
-                    .file_name()
-                    .unwrap()
-                    .to_string_lossy()
-                    .starts_with("Session_")
-            {
-                let metadata_path = path.join("metadata.json");
-                let video_path = path.join("screen.mp4");
-
-                if video_path.exists() {
-                    let mut name = path.file_name().unwrap().to_string_lossy().to_string();
-                    let mut duration = "00:00".to_string();
-                    let mut id = 0;
-
-                    if metadata_path.exists() {
-                        if let Ok(meta_str) = fs::read_to_string(metadata_path) {
-                            if let Ok(meta) = serde_json::from_str::<RecordingMetadata>(&meta_str) {
-                                name = meta.name;
-                                duration = meta.duration;
-                                id = meta.timestamp;
-                            }
-                        }
-                    }
+/// Queues silence detection; `get_silence_analysis` returns the result once
+/// the job is done.
+#[tauri::command]
+#[specta::specta]
+fn detect_silence(
+    app: AppHandle,
+    state: State<AppState>,
+    jobs: State<JobManager>,
+    id: String,
+    threshold_db: Option<f64>,
+    min_duration_ms: Option<u64>,
+) -> Result<Job, String> {
+    let session_dir = session_path(&state, &id)?;
+    if segments::single_file(&session_dir).is_none() {
+        return Err("Recording not found".into());
+    }
+    Ok(jobs.enqueue(
+        &app,
+        JobKind::DetectSilence {
+            session_path: session_dir.to_string_lossy().to_string(),
+            threshold_db: threshold_db.unwrap_or(silence::DEFAULT_THRESHOLD_DB),
+            min_duration_ms: min_duration_ms.unwrap_or(silence::DEFAULT_MIN_DURATION_MS),
+        },
+    ))
+}
 
-                    if id == 0 {
-                        id = entry
-                            .metadata()
-                            .ok()
-                            .and_then(|m| m.created().ok())
-                            .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap().as_secs())
-                            .unwrap_or(0);
-                    }
+/// Runs a `DetectSilence` job; the analysis is cached in the manifest.
+pub(crate) fn detect_session_silence(
+    session_dir: &Path,
+    threshold_db: f64,
+    min_duration_ms: u64,
+    cancel: &AtomicBool,
+    on_progress: &mut dyn FnMut(f64),
+) -> Result<SilenceAnalysis, String> {
+    let video_path = segments::single_file(session_dir).ok_or("Recording not found")?;
+    let session_manifest = load_session_manifest(session_dir, &video_path);
+    silence_analysis(
+        session_dir,
+        &video_path,
+        &session_manifest,
+        threshold_db,
+        min_duration_ms,
+        cancel,
+        on_progress,
+    )
+}
 
-                    let size_bytes = fs::metadata(&video_path).map(|m| m.len()).unwrap_or(0);
-                    let size_mb = (size_bytes as f64) / (1024.0 * 1024.0);
-                    let size_str = format!("{:.1} MB", size_mb);
-
-                    recordings.push(FileRecord {
-                        id,
-                        name,
-                        duration,
-                        size: size_str,
-                        folder: path.file_name().unwrap().to_string_lossy().to_string(),
-                        files: vec!["screen.mp4".to_string()],
-                        full_path: video_path.to_string_lossy().to_string(),
-                    });
-                }
+/// The latest silence analysis of the recording, if any.
+#[tauri::command]
+#[specta::specta]
+fn get_silence_analysis(
+    state: State<AppState>,
+    id: String,
+) -> Result<Option<SilenceAnalysis>, String> {
+    let session_dir = session_path(&state, &id)?;
+    Ok(manifest::load(&session_dir)?.and_then(|m| m.silence))
+}
+
+/// Queues an export without silence. `mode` is "remove" to cut silent
+/// stretches out or "speedup" to play them at `speed` (default 4x); the
+/// job's output is the new file.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+fn export_without_silence(
+    app: AppHandle,
+    state: State<AppState>,
+    jobs: State<JobManager>,
+    id: String,
+    mode: String,
+    speed: Option<f64>,
+    threshold_db: Option<f64>,
+    min_duration_ms: Option<u64>,
+) -> Result<Job, String> {
+    let silent_speed = match mode.as_str() {
+        "remove" => None,
+        "speedup" => {
+            let speed = speed.unwrap_or(4.0);
+            if !(1.0..=16.0).contains(&speed) {
+                return Err("Speed must be between 1x and 16x".into());
             }
+            Some(speed)
+        }
+        other => return Err(format!("Unknown silence mode: {}", other)),
+    };
+
+    let session_dir = session_path(&state, &id)?;
+    if segments::single_file(&session_dir).is_none() {
+        return Err("Recording not found".into());
+    }
+    Ok(jobs.enqueue(
+        &app,
+        JobKind::RemoveSilence {
+            session_path: session_dir.to_string_lossy().to_string(),
+            threshold_db: threshold_db.unwrap_or(silence::DEFAULT_THRESHOLD_DB),
+            min_duration_ms: min_duration_ms.unwrap_or(silence::DEFAULT_MIN_DURATION_MS),
+            silent_speed,
+        },
+    ))
+}
+
+/// Runs a `RemoveSilence` job and returns the new file. Detection counts as
+/// the first tenth of the progress when it isn't cached.
+pub(crate) fn remove_session_silence(
+    session_dir: &Path,
+    threshold_db: f64,
+    min_duration_ms: u64,
+    silent_speed: Option<f64>,
+    cancel: &AtomicBool,
+    on_progress: &mut dyn FnMut(f64),
+) -> Result<PathBuf, String> {
+    let video_path = segments::single_file(session_dir).ok_or("Recording not found")?;
+
+    let session_manifest = load_session_manifest(session_dir, &video_path);
+    let analysis = silence_analysis(
+        session_dir,
+        &video_path,
+        &session_manifest,
+        threshold_db,
+        min_duration_ms,
+        cancel,
+        &mut |fraction| on_progress(fraction * 0.1),
+    )?;
+    if analysis.ranges.is_empty() {
+        return Err("No silence found with these settings".into());
+    }
+
+    let segments =
+        silence::plan_segments(&analysis.ranges, session_manifest.duration_ms, silent_speed);
+    if segments.is_empty() {
+        return Err("Recording is entirely silent".into());
+    }
+
+    let file = edit::claim_output(session_dir, "screen_nosilence")?;
+    let target = session_dir.join(&file);
+    println!(
+        "Exporting {} without silence ({}), {} segment(s)",
+        video_path.display(),
+        if silent_speed.is_some() {
+            "speedup"
+        } else {
+            "remove"
+        },
+        segments.len()
+    );
+    if let Err(e) = edit::render_segments(
+        &video_path,
+        &segments,
+        true,
+        &target,
+        cancel,
+        &mut |fraction| on_progress(0.1 + fraction * 0.9),
+    ) {
+        let _ = fs::remove_file(&target);
+        return Err(e);
+    }
+    edit::embed_markers(
+        &target,
+        &segments,
+        &edit::remap_markers(&session_manifest.markers, &segments),
+    );
+
+    let duration_ms = media::probe(&target)
+        .map(|info| info.duration_ms)
+        .unwrap_or(0);
+    let edit = EditRecord {
+        kind: if silent_speed.is_some() {
+            "silenceSpedUp"
+        } else {
+            "silenceRemoved"
         }
+        .to_string(),
+        file,
+        ranges: silence::kept_ranges(&segments),
+        precise: true,
+        duration_ms,
+        created_at: chrono::Local::now().timestamp_millis() as u64,
+    };
+    // Reloaded, so renames and tags made while rendering are kept.
+    manifest::update(
+        session_dir,
+        || bare_manifest(session_dir),
+        |manifest| manifest.edits.push(edit),
+    )?;
+
+    Ok(target)
+}
+
+/// Queues an export of the recording with `preset`; progress arrives as
+/// `job-progress` events for the returned job.
+#[tauri::command]
+#[specta::specta]
+fn export_recording(
+    app: AppHandle,
+    state: State<AppState>,
+    jobs: State<JobManager>,
+    id: String,
+    preset: ExportPreset,
+) -> Result<Job, String> {
+    let session_dir = session_path(&state, &id)?;
+    if segments::single_file(&session_dir).is_none() {
+        return Err("Recording not found".into());
+    }
+    Ok(jobs.enqueue(
+        &app,
+        JobKind::Export {
+            session_path: session_dir.to_string_lossy().to_string(),
+            preset,
+        },
+    ))
+}
+
+/// Queues a local Whisper transcription. `model` is a file name inside the
+/// app's models folder or an absolute path; defaults to the base model.
+#[tauri::command]
+#[specta::specta]
+fn transcribe_recording(
+    app: AppHandle,
+    state: State<AppState>,
+    jobs: State<JobManager>,
+    id: String,
+    model: Option<String>,
+    language: Option<String>,
+    mux_subtitles: Option<bool>,
+) -> Result<Job, String> {
+    let session_dir = session_path(&state, &id)?;
+    if segments::single_file(&session_dir).is_none() {
+        return Err("Recording not found".into());
     }
+    let model = model.unwrap_or_else(|| transcribe::DEFAULT_MODEL.to_string());
+    let models_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(transcribe::MODELS_DIR);
+    let model_path = transcribe::model_path(&models_dir, &model)?;
+
+    Ok(jobs.enqueue(
+        &app,
+        JobKind::Transcribe {
+            session_path: session_dir.to_string_lossy().to_string(),
+            model_path: model_path.to_string_lossy().to_string(),
+            language: language.filter(|l| !l.is_empty()),
+            mux_subtitles: mux_subtitles.unwrap_or(false),
+        },
+    ))
+}
 
-    // Sort by id (timestamp) descending by default
-    recordings.sort_by(|a, b| b.id.cmp(&a.id));
+#[tauri::command]
+#[specta::specta]
+fn get_transcript(
+    state: State<AppState>,
+    id: String,
+) -> Result<Option<transcribe::Transcript>, String> {
+    Ok(transcribe::load(&session_path(&state, &id)?))
+}
 
-    Ok(recordings)
+#[tauri::command]
+#[specta::specta]
+fn list_jobs(jobs: State<JobManager>) -> Vec<Job> {
+    jobs.list()
 }
 
 #[tauri::command]
-fn delete_recording(path: String) -> Result<(), String> {
-    // path is the folder path
-    let p = std::path::Path::new(&path);
-    if p.exists() && p.is_dir() {
-        fs::remove_dir_all(p).map_err(|e| e.to_string())?;
+#[specta::specta]
+fn cancel_job(app: AppHandle, jobs: State<JobManager>, id: String) -> Result<(), String> {
+    jobs.cancel(&app, &id)
+}
+
+#[tauri::command]
+#[specta::specta]
+fn retry_job(app: AppHandle, jobs: State<JobManager>, id: String) -> Result<Job, String> {
+    jobs.retry(&app, &id)
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn search_recordings(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<search::SearchResult>, String> {
+    search::search(&library_root(&state), &query, limit.unwrap_or(50))
+}
+
+/// Starts watching the save folder, replacing the watcher of a previous one.
+#[tauri::command]
+#[specta::specta]
+fn watch_library(app: AppHandle, state: State<AppState>) -> Result<(), String> {
+    let root = library_root(&state);
+    let mut watcher = state.watcher.lock().unwrap();
+    if watcher.as_ref().is_some_and(|w| w.root() == root) {
+        return Ok(());
     }
+    // Dropping the old watcher stops it before the new one starts.
+    *watcher = None;
+    fs::create_dir_all(&root).map_err(|e| e.to_string())?;
+    *watcher = Some(LibraryWatcher::start(&app, &root)?);
     Ok(())
 }
 
+fn purge_trash(app: &AppHandle, root: &Path) {
+    let purged = trash::purge_expired(root, settings(app).trash_retention_days);
+    if purged > 0 {
+        println!("Purged {} expired recordings from the trash", purged);
+    }
+}
+
+/// Moves the session folder into the trash and returns the id that
+/// `restore_recording` takes to undo it.
 #[tauri::command]
-fn rename_recording(path: String, new_name: String) -> Result<(), String> {
-    let metadata_path = std::path::Path::new(&path).join("metadata.json");
-    if metadata_path.exists() {
-        let meta_str = fs::read_to_string(&metadata_path).map_err(|e| e.to_string())?;
-        if let Ok(mut meta) = serde_json::from_str::<RecordingMetadata>(&meta_str) {
-            meta.name = new_name;
-            let updated = serde_json::to_string(&meta).map_err(|e| e.to_string())?;
-            fs::write(metadata_path, updated).map_err(|e| e.to_string())?;
+#[specta::specta]
+fn delete_recording(app: AppHandle, state: State<AppState>, id: String) -> Result<String, String> {
+    let session_dir = session_path(&state, &id)?;
+    let trash_id = trash::move_to_trash(&session_dir)?;
+    purge_trash(&app, &library_root(&state));
+    Ok(trash_id)
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn list_trash(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<trash::TrashedRecording>, String> {
+    let root = library_root(&state);
+    purge_trash(&app, &root);
+    Ok(trash::list(&root))
+}
+
+/// Returns the restored session folder.
+#[tauri::command]
+#[specta::specta]
+fn restore_recording(state: State<AppState>, id: String) -> Result<String, String> {
+    trash::restore(&library_root(&state), &id).map(|p| p.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+fn empty_trash(state: State<AppState>) -> Result<(), String> {
+    trash::empty(&library_root(&state))
+}
+
+// The folder of the recording in progress.
+fn active_session_dir(state: &AppState) -> Option<PathBuf> {
+    state
+        .recording
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|r| r.output_path.parent().map(Path::to_path_buf))
+}
+
+// Folders retention must not touch: the recording in progress and sessions
+// with queued or running jobs.
+fn sessions_in_use(app: &AppHandle) -> Vec<PathBuf> {
+    let mut in_use = app.state::<JobManager>().busy_sessions();
+    in_use.extend(active_session_dir(&app.state::<AppState>()));
+    in_use
+}
+
+fn enforce_retention(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let root = library_root(&state);
+    purge_trash(app, &root);
+    let removed = retention::enforce(&root, &settings(app).retention, &sessions_in_use(app));
+    if !removed.is_empty() {
+        println!("Retention removed {} recording(s)", removed.len());
+    }
+}
+
+/// Dry run: what enforcing `policy` (default: the saved one) would remove.
+#[tauri::command]
+#[specta::specta]
+async fn preview_retention(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    policy: Option<RetentionPolicy>,
+) -> Result<Vec<RetentionCandidate>, String> {
+    Ok(retention::plan(
+        &library_root(&state),
+        &policy.unwrap_or_else(|| settings(&app).retention),
+        &sessions_in_use(&app),
+    ))
+}
+
+/// Enforces `policy` (default: the saved one) now and returns what it removed.
+#[tauri::command]
+#[specta::specta]
+async fn apply_retention(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    policy: Option<RetentionPolicy>,
+) -> Result<Vec<RetentionCandidate>, String> {
+    let root = library_root(&state);
+    purge_trash(&app, &root);
+    Ok(retention::enforce(
+        &root,
+        &policy.unwrap_or_else(|| settings(&app).retention),
+        &sessions_in_use(&app),
+    ))
+}
+
+#[tauri::command]
+#[specta::specta]
+fn rename_recording(state: State<AppState>, id: String, new_name: String) -> Result<(), String> {
+    let session_dir = &session_path(&state, &id)?;
+    manifest::update(
+        session_dir,
+        || bare_manifest(session_dir),
+        |manifest| manifest.name = new_name,
+    )
+    .map(|_| ())
+}
+
+// A fresh manifest for folders that never got one (e.g. the recording
+// couldn't be probed), so edits still stick.
+fn bare_manifest(session_dir: &Path) -> SessionManifest {
+    let name = session_dir
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let video_file = segments::video_path(session_dir)
+        .and_then(|p| p.file_name().map(|f| f.to_string_lossy().to_string()))
+        .unwrap_or_default();
+    let mut manifest = SessionManifest::new(name, video_file, None);
+    manifest.app_version = String::new();
+    manifest.created_at = created_millis(session_dir);
+    manifest
+}
+
+/// Updates whichever of tags, notes and favorite are given; omitted fields
+/// are left as they are.
+#[tauri::command]
+#[specta::specta]
+fn update_recording_metadata(
+    state: State<AppState>,
+    id: String,
+    tags: Option<Vec<String>>,
+    notes: Option<String>,
+    favorite: Option<bool>,
+) -> Result<(), String> {
+    let session_dir = &session_path(&state, &id)?;
+    manifest::update(
+        session_dir,
+        || bare_manifest(session_dir),
+        |manifest| {
+            if let Some(tags) = tags {
+                manifest.tags = manifest::normalize_tags(&tags);
+            }
+            if let Some(notes) = notes {
+                manifest.notes = notes;
+            }
+            if let Some(favorite) = favorite {
+                manifest.favorite = favorite;
+            }
+        },
+    )
+    .map(|_| ())
+}
+
+#[derive(Serialize, Type)]
+struct TagCount {
+    name: String,
+    count: usize,
+}
+
+/// Every tag used in the library with the number of recordings carrying it,
+/// most used first.
+#[tauri::command]
+#[specta::specta]
+async fn list_tags(state: State<'_, AppState>) -> Result<Vec<TagCount>, String> {
+    let root_dir = library_root(&state);
+    let mut counts: Vec<TagCount> = Vec::new();
+    let Ok(entries) = fs::read_dir(&root_dir) else {
+        return Ok(counts);
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() || !entry.file_name().to_string_lossy().starts_with("Session_") {
+            continue;
+        }
+        let Ok(Some(session_manifest)) = manifest::load(&path) else {
+            continue;
+        };
+        for tag in session_manifest.tags {
+            match counts
+                .iter_mut()
+                .find(|c| c.name.eq_ignore_ascii_case(&tag))
+            {
+                Some(existing) => existing.count += 1,
+                None => counts.push(TagCount {
+                    name: tag,
+                    count: 1,
+                }),
+            }
         }
     }
-    Ok(())
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    Ok(counts)
 }
 
 #[tauri::command]
-async fn get_input_devices(app: tauri::AppHandle) -> Result<DeviceList, String> {
+#[specta::specta]
+async fn get_input_devices() -> Result<DeviceList, String> {
+    probe_dshow_devices()
+}
+
+fn probe_dshow_devices() -> Result<DeviceList, String> {
     // Determine ffmpeg path. In production this should be bundled or located in a known path.
     // For now we use the same path as start_recording or assume it is in PATH if not absolute.
     // Since start_recording uses an absolute path, we should probably stick to that or "ffmpeg" if in PATH.
@@ -282,13 +1292,7 @@ async fn get_input_devices(app: tauri::AppHandle) -> Result<DeviceList, String>
     // Note: The user mentioned "built exe", so it likely uses the bundled ffmpeg or system ffmpeg.
     // Using std::process::Command allows us to hide the window.
 
-    let mut cmd = Command::new("ffmpeg");
-    #[cfg(windows)]
-    {
-        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-    }
-
-    let output = cmd
+    let output = background_command("ffmpeg")
         .args(["-list_devices", "true", "-f", "dshow", "-i", "dummy"])
         .output()
         .map_err(|e| format!("Failed to execute ffmpeg: {}", e))?;
@@ -332,67 +1336,17 @@ async fn get_input_devices(app: tauri::AppHandle) -> Result<DeviceList, String>
     })
 }
 
-#[derive(Serialize)]
-struct DiskInfo {
-    free: u64,
-    total: u64,
-    label: String,
-}
-
+/// Space on the volume holding the save folder.
 #[tauri::command]
-async fn get_disk_info() -> Result<DiskInfo, String> {
-    // On Windows, use wmic to get disk info for C:
-    // wmic logicaldisk where "DeviceID='C:'" get size,freespace,volumename
-    let mut cmd = Command::new("wmic");
-    #[cfg(windows)]
-    {
-        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-    }
-    let output = cmd
-        .args([
-            "logicaldisk",
-            "where",
-            "DeviceID='C:'",
-            "get",
-            "size,freespace,volumename",
-            "/format:list",
-        ])
-        .output()
-        .map_err(|e| format!("Failed to run wmic: {}", e))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut free = 0;
-    let mut total = 0;
-    let mut label = "Local Disk".to_string();
-
-    for line in stdout.lines() {
-        let parts: Vec<&str> = line.split('=').collect();
-        if parts.len() == 2 {
-            let key = parts[0].trim();
-            let val = parts[1].trim();
-            match key {
-                "FreeSpace" => free = val.parse().unwrap_or(0),
-                "Size" => total = val.parse().unwrap_or(0),
-                "VolumeName" => {
-                    if !val.is_empty() {
-                        label = val.to_string()
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
-
-    if label == "Local Disk" {
-        label = "System Drive (C:)".to_string();
-    } else {
-        label = format!("{} (C:)", label);
-    }
-
-    Ok(DiskInfo { free, total, label })
+#[specta::specta]
+async fn get_disk_info(state: State<'_, AppState>) -> Result<disk::DiskInfo, String> {
+    disk::disk_info(&library_root(&state))
 }
 
+/// Lets the user pick a new save folder. It becomes the folder commands are
+/// confined to once it is saved as `savePath`.
 #[tauri::command]
+#[specta::specta]
 async fn select_folder(app: tauri::AppHandle) -> Result<Option<String>, String> {
     use tauri_plugin_dialog::DialogExt;
 
@@ -402,51 +1356,222 @@ async fn select_folder(app: tauri::AppHandle) -> Result<Option<String>, String>
         let _ = tx.send(folder);
     });
 
-    let result = rx.recv().map_err(|e| e.to_string())?;
-    Ok(result.map(|p| p.to_string()))
+    Ok(rx.recv().map_err(|e| e.to_string())?.map(|p| p.to_string()))
+}
+
+#[derive(Clone, Serialize, Deserialize, Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+struct RecordingStarted {
+    session_path: String,
+    trigger: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+struct LowDiskWarning {
+    free_bytes: u64,
+    /// Estimated recording time left before the low-disk stop.
+    minutes_remaining: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Type, tauri_specta::Event)]
+struct RecordingStopped {
+    path: String,
+    size: String,
+    reason: String,
 }
 
 #[tauri::command]
-fn start_recording(state: State<AppState>, options: String) -> Result<String, String> {
-    let mut recording = state.recording.lock().unwrap();
-    if recording.is_some() {
-        return Err("Already recording".into());
-    }
+#[specta::specta]
+fn start_recording(
+    app: AppHandle,
+    state: State<AppState>,
+    options: RecordingOptions,
+) -> Result<String, String> {
+    begin_recording(&app, &state, options, "command")
+}
 
-    let opts: RecordingOptions =
-        serde_json::from_str(&options).map_err(|e| format!("Invalid options JSON: {}", e))?;
+/// Starts recording with a stored profile. `name` names the recording.
+#[tauri::command]
+#[specta::specta]
+fn start_recording_with_profile(
+    app: AppHandle,
+    state: State<AppState>,
+    profiles: State<ProfileStore>,
+    id: String,
+    name: Option<String>,
+) -> Result<String, String> {
+    let mut opts = profiles.get(&id)?.options;
+    opts.name = name;
+    begin_recording(&app, &state, opts, "command")
+}
 
-    println!("Starting recording with parsed options: {:?}", opts);
+#[tauri::command]
+#[specta::specta]
+fn list_profiles(profiles: State<ProfileStore>) -> Vec<RecordingProfile> {
+    profiles.list()
+}
 
-    let mic_vol = opts.mic_volume.unwrap_or(1.0);
-    let sys_vol = opts.system_audio_volume.unwrap_or(1.0);
+#[tauri::command]
+#[specta::specta]
+fn create_profile(
+    profiles: State<ProfileStore>,
+    name: String,
+    options: RecordingOptions,
+) -> Result<RecordingProfile, String> {
+    profiles.create(&name, options)
+}
 
-    // Setup directory
-    let root_dir = if !opts.save_path.is_empty() {
-        PathBuf::from(&opts.save_path)
+#[tauri::command]
+#[specta::specta]
+fn update_profile(
+    profiles: State<ProfileStore>,
+    id: String,
+    name: String,
+    options: RecordingOptions,
+) -> Result<RecordingProfile, String> {
+    profiles.update(&id, &name, options)
+}
+
+#[tauri::command]
+#[specta::specta]
+fn delete_profile(profiles: State<ProfileStore>, id: String) -> Result<(), String> {
+    profiles.delete(&id)
+}
+
+/// JSON document with the given profiles, or all of them.
+#[tauri::command]
+#[specta::specta]
+fn export_profiles(
+    profiles: State<ProfileStore>,
+    ids: Option<Vec<String>>,
+) -> Result<String, String> {
+    profiles.export(ids.as_deref())
+}
+
+#[tauri::command]
+#[specta::specta]
+fn import_profiles(
+    profiles: State<ProfileStore>,
+    json: String,
+) -> Result<Vec<RecordingProfile>, String> {
+    profiles.import(&json)
+}
+
+const DISK_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+fn low_disk_threshold(app: &AppHandle) -> u64 {
+    settings(app)
+        .low_disk_threshold_mb
+        .saturating_mul(1024 * 1024)
+}
+
+// Rough size of a recording per second; native resolution is assumed to be
+// 1080p since the screen size isn't known before capturing.
+fn estimated_bytes_per_second(opts: &RecordingOptions) -> u64 {
+    let audio_kbps = if opts.mic_enabled || opts.system_audio_enabled {
+        192
     } else {
-        dirs::video_dir()
-            .unwrap_or(PathBuf::from("./"))
-            .join("Reframe")
+        0
     };
+    disk::recording_bytes_per_second(
+        opts.width.unwrap_or(1920),
+        opts.height.unwrap_or(1080),
+        opts.framerate.unwrap_or(30),
+        audio_kbps,
+    )
+}
 
-    let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
-    let session_dir = root_dir.join(format!("Session_{}", timestamp));
+// Refuses to start below the low-disk threshold and warns when only a few
+// minutes of recording would fit before the recording gets stopped.
+fn preflight_disk_space(
+    app: &AppHandle,
+    root: &Path,
+    opts: &RecordingOptions,
+) -> Result<(), String> {
+    let free = match disk::free_space(root) {
+        Ok(free) => free,
+        Err(e) => {
+            println!("Skipping disk space check: {}", e);
+            return Ok(());
+        }
+    };
+    let threshold = low_disk_threshold(app);
+    if free <= threshold {
+        return Err(format!(
+            "Not enough disk space: {:.1} GB free, recordings need more than {:.1} GB",
+            free as f64 / GIB,
+            threshold as f64 / GIB
+        ));
+    }
 
-    fs::create_dir_all(&session_dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    let rate = estimated_bytes_per_second(opts).max(1);
+    let minutes_remaining = (free - threshold) / rate / 60;
+    println!(
+        "{} MB free, about {} minutes of recording",
+        free / 1024 / 1024,
+        minutes_remaining
+    );
+    if minutes_remaining < disk::PREFLIGHT_WARN_MINUTES {
+        let _ = LowDiskWarning {
+            free_bytes: free,
+            minutes_remaining,
+        }
+        .emit(app);
+    }
+    Ok(())
+}
 
-    let output_file = session_dir.join("screen.mp4");
-    let output_path_str = output_file.to_string_lossy().to_string();
+// Checks shared by recordings and the replay buffer before FFmpeg starts.
+fn prepare_capture(
+    app: &AppHandle,
+    root: &Path,
+    opts: &mut RecordingOptions,
+) -> Result<(), String> {
+    opts.validate()?;
+    resolve_mic_device(opts)?;
+    preflight_disk_space(app, root, opts)
+}
+
+// Polls free space on the session's volume for as long as that session is
+// recording and stops it once the threshold is crossed.
+fn spawn_disk_monitor(app: AppHandle, session_dir: PathBuf) {
+    let threshold = low_disk_threshold(&app);
+    std::thread::spawn(move || loop {
+        std::thread::sleep(DISK_CHECK_INTERVAL);
+        let state = app.state::<AppState>();
+        if active_session_dir(&state).as_deref() != Some(session_dir.as_path()) {
+            break;
+        }
+        match disk::free_space(&session_dir) {
+            Ok(free) if free < threshold => {
+                println!(
+                    "Free space down to {} MB, stopping recording",
+                    free / 1024 / 1024
+                );
+                if let Err(e) = end_recording(&app, &state, "low_disk") {
+                    println!("Failed to stop recording: {}", e);
+                }
+                break;
+            }
+            Ok(_) => {}
+            Err(e) => println!("Failed to check free space: {}", e),
+        }
+    });
+}
+
+// FFmpeg reading the screen and audio sources in `opts` and encoding them,
+// ready for output arguments. Shared by recordings and the replay buffer.
+fn capture_command(opts: &RecordingOptions) -> Command {
+    let mic_vol = opts.mic_volume.unwrap_or(1.0);
+    let sys_vol = opts.system_audio_volume.unwrap_or(1.0);
 
     // Get FFmpeg path (assume in PATH for simplicity, or specific if needed)
     let ffmpeg_path = "ffmpeg";
 
     // Build FFmpeg command using std::process::Command
-    let mut cmd = Command::new(ffmpeg_path);
-    #[cfg(windows)]
-    {
-        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-    }
+    let mut cmd = background_command(ffmpeg_path);
 
     cmd.stdin(Stdio::piped())
         .stdout(Stdio::inherit())
@@ -493,26 +1618,25 @@ fn start_recording(state: State<AppState>, options: String) -> Result<String, St
     ]);
 
     // Apply scaling if requested
+    let mut video_filters = Vec::new();
     if let (Some(w), Some(h)) = (opts.width, opts.height) {
-        println!("Applying video scaling: {}x{}", w, h);
-        cmd.args(["-vf", &format!("scale={}:{}:flags=lanczos", w, h)]);
+        let mode = opts
+            .scale_mode
+            .unwrap_or_else(|| ScaleMode::for_capture(opts.capture_mode.as_deref()));
+        println!("Applying video scaling: {}x{} ({:?})", w, h, mode);
+        video_filters.extend(scaling::filters(mode, w, h));
     } else {
         println!("No resolution scaling applied (Original/Native).");
+        video_filters.push(scaling::EVEN_CROP.to_string());
+    }
+    video_filters.extend(opts.filters.clone());
+    if !video_filters.is_empty() {
+        cmd.args(["-vf", &video_filters.join(",")]);
     }
 
-    // Encoding settings for video
-    cmd.args([
-        "-c:v",
-        "libx264",
-        "-pix_fmt",
-        "yuv420p",
-        "-preset",
-        "superfast",
-        "-g",
-        &fps, // Keyframe interval matches fps (1 sec)
-        "-crf",
-        "23",
-    ]);
+    // Encoding settings for video; keyframe interval matches fps (1 sec)
+    println!("Encoding with {:?}", opts.encoder);
+    cmd.args(opts.encoder.args(opts.framerate.unwrap_or(30)));
 
     // Handle audio encoding based on number of audio sources
     if audio_input_count == 2 {
@@ -562,7 +1686,58 @@ fn start_recording(state: State<AppState>, options: String) -> Result<String, St
         cmd.arg("-an");
     }
 
-    cmd.args(["-movflags", "+faststart", &output_path_str]);
+    cmd
+}
+
+// Shared by the `start_recording` command and the global hotkey, so both
+// paths spawn FFmpeg identically. `trigger` is echoed in `recording-started`.
+fn begin_recording(
+    app: &AppHandle,
+    state: &AppState,
+    mut opts: RecordingOptions,
+    trigger: &str,
+) -> Result<String, String> {
+    let mut recording = state.recording.lock().unwrap();
+    if recording.is_some() {
+        return Err("Already recording".into());
+    }
+    if state.replay.lock().unwrap().is_some() {
+        return Err("Stop the replay buffer before recording".into());
+    }
+
+    println!("Starting recording with parsed options: {:?}", opts);
+
+    // Setup directory
+    let root_dir = library_root(state);
+    prepare_capture(app, &root_dir, &mut opts)?;
+
+    let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+    // Two recordings started within a second would otherwise share a folder.
+    let session_dir = trash::free_name(&root_dir, &format!("Session_{}", timestamp));
+
+    fs::create_dir_all(&session_dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let segment_seconds = segments::segment_seconds(
+        opts.segment_minutes,
+        opts.segment_size_mb,
+        estimated_bytes_per_second(&opts),
+    );
+    let output_file = if segment_seconds.is_some() {
+        segments::first_part(&session_dir, opts.container)
+    } else {
+        session_dir.join(format!("screen.{}", opts.container.extension()))
+    };
+
+    let mut cmd = capture_command(&opts);
+    if let Some(seconds) = segment_seconds {
+        println!("Splitting recording every {} seconds", seconds);
+        cmd.args(segments::output_args(&session_dir, seconds, opts.container));
+    } else {
+        if opts.container == Container::Mp4 {
+            cmd.args(["-movflags", "+faststart"]);
+        }
+        cmd.arg(&output_file);
+    }
 
     println!("Spawning FFmpeg...");
     let child = cmd
@@ -570,75 +1745,335 @@ fn start_recording(state: State<AppState>, options: String) -> Result<String, St
         .map_err(|e| format!("Failed to spawn ffmpeg: {}", e))?;
     println!("FFmpeg spawned with PID: {:?}", child.id());
 
+    let name = opts
+        .name
+        .clone()
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| {
+            format!(
+                "Recording {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+            )
+        });
+    let video_file = output_file
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let session_manifest = SessionManifest::new(name, video_file, Some(opts));
+    if let Err(e) = manifest::save(&session_dir, &session_manifest) {
+        println!("Failed to write initial manifest: {}", e);
+    }
+
     *recording = Some(RecordingState {
         process: child,
         output_path: output_file,
+        started_at: Instant::now(),
+        manifest: session_manifest,
     });
+    spawn_disk_monitor(app.clone(), session_dir.clone());
+
+    let session_path = session_dir.to_string_lossy().to_string();
+    let _ = RecordingStarted {
+        session_path: session_path.clone(),
+        trigger: trigger.to_string(),
+    }
+    .emit(app);
+
+    Ok(session_path)
+}
+
+#[derive(Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+struct ReplayStatus {
+    running: bool,
+    buffer_seconds: u32,
+}
 
-    Ok(session_dir.to_string_lossy().to_string())
+fn replay_status(state: &AppState) -> ReplayStatus {
+    let mut replay = state.replay.lock().unwrap();
+    // FFmpeg exiting on its own (a device unplugged) ends the buffer.
+    if replay.as_mut().is_some_and(|buffer| !buffer.is_running()) {
+        if let Some(buffer) = replay.take() {
+            buffer.stop();
+        }
+    }
+    ReplayStatus {
+        running: replay.is_some(),
+        buffer_seconds: replay.as_ref().map_or(0, |b| b.buffer_seconds),
+    }
 }
 
-#[derive(Serialize)]
+/// Starts capturing into a rolling buffer that keeps the last `seconds`
+/// (two minutes by default) until `save_replay` writes them out.
+#[tauri::command]
+#[specta::specta]
+fn start_replay_buffer(
+    app: AppHandle,
+    state: State<AppState>,
+    mut options: RecordingOptions,
+    seconds: Option<u32>,
+) -> Result<ReplayStatus, String> {
+    let buffer_seconds = seconds
+        .unwrap_or(replay::DEFAULT_BUFFER_SECONDS)
+        .clamp(1, replay::MAX_BUFFER_SECONDS);
+    {
+        let recording = state.recording.lock().unwrap();
+        let mut replay = state.replay.lock().unwrap();
+        if replay.is_some() {
+            return Err("Replay buffer is already running".into());
+        }
+        if recording.is_some() {
+            return Err("Stop recording before starting the replay buffer".into());
+        }
+        let dir = replay::temp_dir();
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create replay folder: {}", e))?;
+        // The rolling segments are what keeps writing, so check their drive.
+        prepare_capture(&app, &dir, &mut options)?;
+        let mut cmd = capture_command(&options);
+        cmd.args(replay::output_args(&dir, buffer_seconds));
+        let child = cmd
+            .spawn()
+            .map_err(|e| format!("Failed to spawn ffmpeg: {}", e))?;
+        println!(
+            "Replay buffer started ({}s), FFmpeg PID: {:?}",
+            buffer_seconds,
+            child.id()
+        );
+        *replay = Some(ReplayBuffer::new(child, dir, buffer_seconds, options));
+    }
+
+    let status = replay_status(&state);
+    let _ = ReplayBufferChanged(status.clone()).emit(&app);
+    Ok(status)
+}
+
+#[tauri::command]
+#[specta::specta]
+fn stop_replay_buffer(app: AppHandle, state: State<AppState>) -> Result<(), String> {
+    let buffer = state
+        .replay
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or("Replay buffer is not running")?;
+    buffer.stop();
+    let _ = ReplayBufferChanged(replay_status(&state)).emit(&app);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+fn get_replay_status(state: State<AppState>) -> ReplayStatus {
+    replay_status(&state)
+}
+
+/// Saves the last `seconds` of the replay buffer (all of it by default) as a
+/// new recording and returns its session folder.
+#[tauri::command]
+#[specta::specta]
+async fn save_replay(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    seconds: Option<u32>,
+    name: Option<String>,
+) -> Result<String, String> {
+    // Joining the segments takes a while; the buffer stays usable meanwhile.
+    let (dir, buffer_seconds, options) = {
+        let replay = state.replay.lock().unwrap();
+        let buffer = replay.as_ref().ok_or("Replay buffer is not running")?;
+        (
+            buffer.dir().to_path_buf(),
+            buffer.buffer_seconds,
+            buffer.options.clone(),
+        )
+    };
+    let seconds = seconds.unwrap_or(buffer_seconds).clamp(1, buffer_seconds);
+
+    let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+    let session_dir = trash::free_name(&library_root(&state), &format!("Session_{}", timestamp));
+    fs::create_dir_all(&session_dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    let video_path = session_dir.join("screen.mp4");
+    if let Err(e) = replay::save(&dir, seconds, &video_path) {
+        let _ = fs::remove_dir_all(&session_dir);
+        return Err(e);
+    }
+
+    let name = name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| {
+        format!(
+            "Replay {}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+        )
+    });
+    let mut session_manifest = SessionManifest::new(name, "screen.mp4".into(), Some(options));
+    match media::probe(&video_path) {
+        Ok(info) => {
+            session_manifest.duration_ms = info.duration_ms;
+            session_manifest.media = Some(info);
+        }
+        Err(e) => println!("Failed to probe replay: {}", e),
+    }
+    manifest::save(&session_dir, &session_manifest)?;
+
+    let session_path = session_dir.to_string_lossy().to_string();
+    println!("Saved {}s replay to {}", seconds, session_path);
+    app.state::<JobManager>().enqueue(
+        &app,
+        JobKind::Thumbnails {
+            session_path: session_path.clone(),
+        },
+    );
+    let retention_handle = app.clone();
+    std::thread::spawn(move || enforce_retention(&retention_handle));
+    Ok(session_path)
+}
+
+#[derive(Serialize, Type)]
 struct StopResult {
     path: String,
     size: String,
 }
 
 #[tauri::command]
-fn stop_recording(state: State<AppState>) -> Result<StopResult, String> {
-    let mut recording = state.recording.lock().unwrap();
+#[specta::specta]
+fn stop_recording(app: AppHandle, state: State<AppState>) -> Result<StopResult, String> {
+    end_recording(&app, &state, "command")
+}
+
+/// Asks FFmpeg to finish its output with `q`, killing it after 5 seconds.
+pub(crate) fn stop_ffmpeg(process: &mut Child) {
+    // Send 'q\n' to stdin to gracefully stop FFmpeg
+    if let Some(ref mut stdin) = process.stdin {
+        let _ = stdin.write_all(b"q\n");
+        let _ = stdin.flush();
+    }
+
+    // Wait for FFmpeg to finish (up to 5 seconds)
+    println!("Waiting for FFmpeg to finish...");
+    for i in 0..50 {
+        match process.try_wait() {
+            Ok(Some(status)) => {
+                println!("FFmpeg exited with status: {:?}", status);
+                break;
+            }
+            Ok(None) => {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+            }
+            Err(e) => {
+                println!("Error checking process status: {}", e);
+                break;
+            }
+        }
+        if i == 49 {
+            println!("FFmpeg didn't exit gracefully, killing...");
+            let _ = process.kill();
+        }
+    }
+}
 
-    if let Some(mut rec_state) = recording.take() {
-        println!("Stopping recording gracefully...");
+// Counterpart of `begin_recording`; `reason` is echoed in `recording-stopped`.
+fn end_recording(app: &AppHandle, state: &AppState, reason: &str) -> Result<StopResult, String> {
+    // Taken out first: stopping FFmpeg, probing and remuxing chapters take
+    // seconds, and the hotkey and tray handlers shouldn't wait on the lock.
+    let Some(mut rec_state) = state.recording.lock().unwrap().take() else {
+        return Err("Not recording".into());
+    };
+    println!("Stopping recording gracefully...");
+    let duration_ms = rec_state.started_at.elapsed().as_millis() as u64;
+
+    stop_ffmpeg(&mut rec_state.process);
+
+    let mut session_manifest = rec_state.manifest;
+    let session_dir = rec_state
+        .output_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    session_manifest.parts = segments::parts(&session_dir);
+    let split = !session_manifest.parts.is_empty();
+    if split {
+        session_manifest.video_file = session_manifest.parts[0].clone();
+    }
 
-        // Send 'q\n' to stdin to gracefully stop FFmpeg
-        if let Some(ref mut stdin) = rec_state.process.stdin {
-            let _ = stdin.write_all(b"q\n");
-            let _ = stdin.flush();
+    // Prefer the real stream duration over the wall-clock estimate, which
+    // also counts FFmpeg starting up and shutting down.
+    session_manifest.duration_ms = duration_ms;
+    let probed = if split {
+        segments::probe(&session_dir, &session_manifest.parts)
+    } else {
+        media::probe(&rec_state.output_path).map(|info| (info.duration_ms, info))
+    };
+    match probed {
+        Ok((probed_ms, info)) => {
+            if probed_ms > 0 {
+                session_manifest.duration_ms = probed_ms;
+            }
+            session_manifest.media = Some(info);
         }
+        Err(e) => println!("Failed to probe recording: {}", e),
+    }
 
-        // Wait for FFmpeg to finish (up to 5 seconds)
-        println!("Waiting for FFmpeg to finish...");
-        for i in 0..50 {
-            match rec_state.process.try_wait() {
-                Ok(Some(status)) => {
-                    println!("FFmpeg exited with status: {:?}", status);
-                    break;
-                }
-                Ok(None) => {
-                    std::thread::sleep(std::time::Duration::from_millis(100));
-                }
-                Err(e) => {
-                    println!("Error checking process status: {}", e);
-                    break;
+    // Chapter times are relative to the whole recording, so they're only
+    // embedded into single files; split recordings keep them in the manifest.
+    if !session_manifest.markers.is_empty() && !split {
+        println!(
+            "Embedding {} chapter markers...",
+            session_manifest.markers.len()
+        );
+        match chapters::embed_chapters(
+            &rec_state.output_path,
+            &session_manifest.markers,
+            session_manifest.duration_ms,
+        ) {
+            Ok(()) => {
+                // Remuxing replaced the file the media info describes.
+                if let Ok(info) = media::probe(&rec_state.output_path) {
+                    session_manifest.media = Some(info);
                 }
             }
-            if i == 49 {
-                println!("FFmpeg didn't exit gracefully, killing...");
-                let _ = rec_state.process.kill();
-            }
+            Err(e) => println!("Failed to embed chapters: {}", e),
         }
-
-        // Get file size
-        let size_bytes = fs::metadata(&rec_state.output_path)
-            .map(|m| m.len())
-            .unwrap_or(0);
-        let size_mb = (size_bytes as f64) / (1024.0 * 1024.0);
-        let size_str = format!("{:.1} MB", size_mb);
-
-        // Return the output path and size
-        let path = rec_state.output_path.to_string_lossy().to_string();
-        println!("Recording stopped, output: {}, size: {}", path, size_str);
-        Ok(StopResult {
-            path,
-            size: size_str,
-        })
+    }
+    if let Err(e) = manifest::save(&session_dir, &session_manifest) {
+        println!("Failed to write manifest: {}", e);
+    }
+    app.state::<JobManager>().enqueue(
+        app,
+        JobKind::Thumbnails {
+            session_path: session_dir.to_string_lossy().to_string(),
+        },
+    );
+    let retention_handle = app.clone();
+    std::thread::spawn(move || enforce_retention(&retention_handle));
+
+    // Get file size
+    let size_bytes = if split {
+        segments::total_size(&session_dir, &session_manifest.parts)
     } else {
-        Err("Not recording".into())
+        fs::metadata(&rec_state.output_path)
+            .map(|m| m.len())
+            .unwrap_or(0)
+    };
+    let size_mb = (size_bytes as f64) / (1024.0 * 1024.0);
+    let size_str = format!("{:.1} MB", size_mb);
+
+    // Return the output path and size
+    let path = rec_state.output_path.to_string_lossy().to_string();
+    println!("Recording stopped, output: {}, size: {}", path, size_str);
+    let _ = RecordingStopped {
+        path: path.clone(),
+        size: size_str.clone(),
+        reason: reason.to_string(),
     }
+    .emit(app);
+    Ok(StopResult {
+        path,
+        size: size_str,
+    })
 }
 
 #[tauri::command]
+#[specta::specta]
 async fn toggle_webcam(app: tauri::AppHandle, show: bool) -> Result<(), String> {
     if show {
         // Look for the window defined in tauri.conf.json first
@@ -680,8 +2115,31 @@ async fn toggle_webcam(app: tauri::AppHandle, show: bool) -> Result<(), String>
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let bindings = bindings::builder();
     tauri::Builder::default()
-        .setup(|app| {
+        .invoke_handler(bindings.invoke_handler())
+        .setup(move |app| {
+            // Before anything that could emit an event.
+            bindings.mount_events(app);
+            let handle = app.handle().clone();
+            let data_dir = app.path().app_data_dir()?;
+            app.manage(JobManager::load(&data_dir));
+            app.manage(ProfileStore::load(&data_dir));
+            app.manage(SettingsStore::load(&data_dir));
+            JobManager::start_workers(&handle);
+
+            let state = app.state::<AppState>();
+            let settings = settings(&handle);
+            // Stored settings are validated on load, so the hotkeys compile.
+            *state.hotkeys.lock().unwrap() =
+                HotkeyMap::compile(settings.hotkeys).unwrap_or_default();
+            spawn_global_listener(handle.clone(), &state);
+
+            set_save_root(&handle, &state, &settings.save_path);
+            purge_trash(&handle, &library_root(&state));
+            let retention_handle = handle.clone();
+            std::thread::spawn(move || enforce_retention(&retention_handle));
+
             // Configure overlay window to be click-through
             if let Some(overlay) = app.get_webview_window("overlay") {
                 // We want the overlay to be transparent to mouse events so valid clicks pass through
@@ -697,36 +2155,28 @@ pub fn run() {
                 // If the controls window is closed, we should exit the entire app
                 // to prevent the overlay from hanging around + stop any recording.
                 if window.label() == "controls" {
+                    let state = window.state::<AppState>();
+                    if let Some(buffer) = state.replay.lock().unwrap().take() {
+                        buffer.stop();
+                    }
+                    window.state::<SettingsStore>().flush();
                     std::process::exit(0);
                 }
             }
         })
         .manage(AppState {
             recording: Mutex::new(None),
+            save_root: Mutex::new(save_root("")),
             listener_running: Mutex::new(false),
+            hotkeys: Mutex::new(HotkeyMap::default()),
+            library: Mutex::new(LibraryCache::default()),
+            watcher: Mutex::new(None),
+            replay: Mutex::new(None),
         })
-        .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_fs::init())
-        .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
-        .invoke_handler(tauri::generate_handler![
-            start_recording,
-            stop_recording,
-            get_input_devices,
-            toggle_webcam,
-            open_folder,
-            open_file,
-            save_metadata,
-            list_recordings,
-            delete_recording,
-            rename_recording,
-            start_global_listener,
-            get_disk_info,
-            select_folder
-        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
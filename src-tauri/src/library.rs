@@ -0,0 +1,459 @@
+use crate::jobs::{JobKind, JobManager};
+use crate::manifest;
+use crate::media::TrackInfo;
+use crate::segments;
+use crate::thumbnails;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
+
+#[derive(Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRecord {
+    /// The session folder name, unique within the save root.
+    pub id: String,
+    pub name: String,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: u64,
+    pub duration_ms: u64,
+    pub size_bytes: u64,
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+    pub bitrate: u64,
+    pub video_codec: Option<String>,
+    pub audio_tracks: Vec<TrackInfo>,
+    pub folder: String,
+    pub files: Vec<String>,
+    pub full_path: String,
+    pub thumbnail: Option<String>,
+    pub preview_sprite: Option<String>,
+    pub preview_frames: u32,
+    pub tags: Vec<String>,
+    pub notes: String,
+    pub favorite: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum SortKey {
+    #[default]
+    Date,
+    Name,
+    Duration,
+    Size,
+}
+
+/// Sorting, filtering and paging for `list_recordings`. Every field is
+/// optional; the default is newest first, unfiltered, everything.
+#[derive(Clone, Debug, Default, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ListQuery {
+    pub sort: SortKey,
+    /// Defaults to newest/longest/largest first and A-Z for names.
+    pub ascending: Option<bool>,
+    /// Creation time bounds in milliseconds since the Unix epoch, inclusive.
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+    pub min_duration_ms: Option<u64>,
+    pub tag: Option<String>,
+    /// Container extension ("mp4") or video codec ("h264").
+    pub format: Option<String>,
+    pub favorites_only: bool,
+    /// Restricts the listing to these session folders, e.g. search hits.
+    pub folders: Option<Vec<String>>,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingPage {
+    pub items: Vec<FileRecord>,
+    /// Matches before paging.
+    pub total: usize,
+    pub offset: usize,
+}
+
+// Anything that changes a record changes one of these: files added/removed
+// in the folder, the manifest rewritten, or the video replaced.
+type Stamp = (u64, u64, u64, u64);
+
+fn modified_millis(path: &Path) -> Option<(u64, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    let modified = meta
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_millis() as u64;
+    Some((modified, meta.len()))
+}
+
+fn session_stamp(session_dir: &Path, video_path: &Path) -> Stamp {
+    let (dir_modified, _) = modified_millis(session_dir).unwrap_or_default();
+    let (manifest_modified, _) =
+        modified_millis(&session_dir.join(manifest::MANIFEST_FILE)).unwrap_or_default();
+    let (video_modified, video_size) = modified_millis(video_path).unwrap_or_default();
+    (dir_modified, manifest_modified, video_modified, video_size)
+}
+
+struct CachedRecord {
+    stamp: Stamp,
+    record: FileRecord,
+}
+
+/// Records built from session folders, reused until the folder changes on
+/// disk. Cleared whenever a different save root is listed.
+#[derive(Default)]
+pub struct LibraryCache {
+    root: PathBuf,
+    records: HashMap<String, CachedRecord>,
+}
+
+// A session whose cached record is missing or out of date.
+struct Stale {
+    folder: String,
+    path: PathBuf,
+    video_path: PathBuf,
+}
+
+impl LibraryCache {
+    // Drops folders that are gone and splits the rest into up-to-date
+    // records and sessions that need building.
+    fn scan(&mut self, root: &Path) -> Result<(Vec<FileRecord>, Vec<Stale>), String> {
+        if self.root != root {
+            self.root = root.to_path_buf();
+            self.records.clear();
+        }
+
+        let mut seen = HashSet::new();
+        let mut fresh = Vec::new();
+        let mut stale = Vec::new();
+        for entry in fs::read_dir(root).map_err(|e| e.to_string())?.flatten() {
+            let path = entry.path();
+            let folder = entry.file_name().to_string_lossy().to_string();
+            if !path.is_dir() || !folder.starts_with("Session_") {
+                continue;
+            }
+            let Some(video_path) = segments::video_path(&path) else {
+                continue;
+            };
+            seen.insert(folder.clone());
+
+            let stamp = session_stamp(&path, &video_path);
+            match self.records.get(&folder) {
+                Some(cached) if cached.stamp == stamp => fresh.push(cached.record.clone()),
+                _ => stale.push(Stale {
+                    folder,
+                    path,
+                    video_path,
+                }),
+            }
+        }
+        self.records.retain(|folder, _| seen.contains(folder));
+        Ok((fresh, stale))
+    }
+}
+
+fn build_record(app: &AppHandle, path: &Path, video_path: &Path, folder: &str) -> FileRecord {
+    let session_manifest = crate::load_session_manifest(path, video_path);
+    let media = session_manifest.media.unwrap_or_default();
+    let previews = thumbnails::existing_previews(video_path);
+    if previews.poster.is_none() || (previews.sprite.is_none() && session_manifest.duration_ms > 0)
+    {
+        app.state::<JobManager>().enqueue_unless_failed(
+            app,
+            JobKind::Thumbnails {
+                session_path: path.to_string_lossy().to_string(),
+            },
+        );
+    }
+
+    let mut files: Vec<String> = fs::read_dir(path)
+        .map(|dir| {
+            dir.flatten()
+                .filter(|f| f.path().is_file())
+                .map(|f| f.file_name().to_string_lossy().to_string())
+                .filter(|f| !f.starts_with(manifest::MANIFEST_FILE))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+
+    FileRecord {
+        id: folder.to_string(),
+        name: session_manifest.name,
+        created_at: session_manifest.created_at,
+        duration_ms: session_manifest.duration_ms,
+        size_bytes: if session_manifest.parts.is_empty() {
+            fs::metadata(video_path).map(|m| m.len()).unwrap_or(0)
+        } else {
+            segments::total_size(path, &session_manifest.parts)
+        },
+        width: media.width,
+        height: media.height,
+        fps: media.fps,
+        bitrate: media.bitrate,
+        video_codec: media.video_codec.clone(),
+        audio_tracks: media.audio_tracks().cloned().collect(),
+        folder: folder.to_string(),
+        files,
+        full_path: video_path.to_string_lossy().to_string(),
+        thumbnail: previews.poster.map(|p| p.to_string_lossy().to_string()),
+        preview_sprite: previews.sprite.map(|p| p.to_string_lossy().to_string()),
+        preview_frames: thumbnails::SPRITE_FRAMES,
+        tags: session_manifest.tags,
+        notes: session_manifest.notes,
+        favorite: session_manifest.favorite,
+    }
+}
+
+fn matches(record: &FileRecord, query: &ListQuery) -> bool {
+    let format_matches = |format: &str| {
+        Path::new(&record.full_path)
+            .extension()
+            .is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case(format))
+            || record
+                .video_codec
+                .as_deref()
+                .is_some_and(|codec| codec.eq_ignore_ascii_case(format))
+    };
+
+    query.from.is_none_or(|from| record.created_at >= from)
+        && query.to.is_none_or(|to| record.created_at <= to)
+        && query
+            .min_duration_ms
+            .is_none_or(|min| record.duration_ms >= min)
+        && query
+            .tag
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .is_none_or(|tag| record.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        && query
+            .format
+            .as_deref()
+            .filter(|f| !f.is_empty())
+            .is_none_or(format_matches)
+        && (!query.favorites_only || record.favorite)
+        && query
+            .folders
+            .as_ref()
+            .is_none_or(|folders| folders.contains(&record.folder))
+}
+
+/// Lists the recordings under `root` that match `query`, one page at a time.
+/// Sessions that changed are probed with `cache` unlocked, so a large first
+/// listing doesn't hold up other callers.
+pub fn list(
+    cache: &Mutex<LibraryCache>,
+    app: &AppHandle,
+    root: &Path,
+    query: &ListQuery,
+) -> Result<RecordingPage, String> {
+    if !root.exists() {
+        fs::create_dir_all(root).ok();
+        return Ok(RecordingPage {
+            items: Vec::new(),
+            total: 0,
+            offset: 0,
+        });
+    }
+    let (mut records, stale) = cache.lock().unwrap().scan(root)?;
+
+    let built: Vec<(String, CachedRecord)> = stale
+        .into_iter()
+        .map(|s| {
+            let record = build_record(app, &s.path, &s.video_path, &s.folder);
+            // Building may rewrite the manifest (fresh probe), so stamp after.
+            let stamp = session_stamp(&s.path, &s.video_path);
+            (s.folder, CachedRecord { stamp, record })
+        })
+        .collect();
+    records.extend(built.iter().map(|(_, c)| c.record.clone()));
+    let mut cache = cache.lock().unwrap();
+    // The save root may have been switched while probing.
+    if cache.root == root {
+        cache.records.extend(built);
+    }
+    drop(cache);
+
+    Ok(select(records, query))
+}
+
+// Filters, sorts and pages the records of a listing.
+fn select(records: Vec<FileRecord>, query: &ListQuery) -> RecordingPage {
+    let mut items: Vec<FileRecord> = records.into_iter().filter(|r| matches(r, query)).collect();
+
+    let ascending = query.ascending.unwrap_or(query.sort == SortKey::Name);
+    items.sort_by(|a, b| {
+        let order = match query.sort {
+            SortKey::Date => a.created_at.cmp(&b.created_at),
+            SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortKey::Duration => a.duration_ms.cmp(&b.duration_ms),
+            SortKey::Size => a.size_bytes.cmp(&b.size_bytes),
+        };
+        // Newest first, then the unique folder name, breaks ties so pages
+        // stay stable.
+        let order = if ascending { order } else { order.reverse() };
+        order
+            .then_with(|| b.created_at.cmp(&a.created_at))
+            .then_with(|| b.id.cmp(&a.id))
+    });
+
+    let total = items.len();
+    let items = items
+        .into_iter()
+        .skip(query.offset)
+        .take(query.limit.unwrap_or(usize::MAX))
+        .collect();
+    RecordingPage {
+        items,
+        total,
+        offset: query.offset,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(folder: &str, created_at: u64, name: &str, duration_ms: u64) -> FileRecord {
+        FileRecord {
+            id: folder.into(),
+            name: name.into(),
+            created_at,
+            duration_ms,
+            size_bytes: duration_ms * 10,
+            width: 1920,
+            height: 1080,
+            fps: 30.0,
+            bitrate: 0,
+            video_codec: Some("h264".into()),
+            audio_tracks: Vec::new(),
+            folder: folder.into(),
+            files: vec!["screen.mp4".into()],
+            full_path: format!("/library/{}/screen.mp4", folder),
+            thumbnail: None,
+            preview_sprite: None,
+            preview_frames: thumbnails::SPRITE_FRAMES,
+            tags: Vec::new(),
+            notes: String::new(),
+            favorite: false,
+        }
+    }
+
+    fn library() -> Vec<FileRecord> {
+        let mut demo = record("Session_b", 2_000, "demo", 60_000);
+        demo.tags = vec!["Work".into()];
+        demo.favorite = true;
+        let mut mkv = record("Session_c", 3_000, "Bug report", 5_000);
+        mkv.full_path = "/library/Session_c/screen.mkv".into();
+        mkv.video_codec = Some("hevc".into());
+        vec![
+            record("Session_a", 1_000, "Alpha", 30_000),
+            demo,
+            mkv,
+            // Same creation time as Session_c, e.g. a folder with no
+            // readable timestamp.
+            record("Session_d", 3_000, "alpha", 30_000),
+        ]
+    }
+
+    fn ids(page: &RecordingPage) -> Vec<&str> {
+        page.items.iter().map(|r| r.id.as_str()).collect()
+    }
+
+    #[test]
+    fn filters_on_every_query_field() {
+        let query = |change: fn(&mut ListQuery)| {
+            let mut query = ListQuery::default();
+            change(&mut query);
+            query
+        };
+        let cases: [(ListQuery, &[&str]); 7] = [
+            (
+                query(|q| q.from = Some(2_000)),
+                &["Session_b", "Session_c", "Session_d"],
+            ),
+            (query(|q| q.to = Some(2_000)), &["Session_a", "Session_b"]),
+            (
+                query(|q| q.min_duration_ms = Some(30_000)),
+                &["Session_a", "Session_b", "Session_d"],
+            ),
+            (query(|q| q.tag = Some("work".into())), &["Session_b"]),
+            (query(|q| q.format = Some("MKV".into())), &["Session_c"]),
+            (query(|q| q.format = Some("hevc".into())), &["Session_c"]),
+            (query(|q| q.favorites_only = true), &["Session_b"]),
+        ];
+        for (query, expected) in cases {
+            let mut found: Vec<String> = library()
+                .into_iter()
+                .filter(|r| matches(r, &query))
+                .map(|r| r.id)
+                .collect();
+            found.sort();
+            assert_eq!(found, expected, "{:?}", query);
+        }
+        let folders = ListQuery {
+            folders: Some(vec!["Session_a".into()]),
+            tag: Some(String::new()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&select(library(), &folders)), ["Session_a"]);
+    }
+
+    #[test]
+    fn sorts_newest_first_and_breaks_ties_by_folder() {
+        let page = select(library(), &ListQuery::default());
+        assert_eq!(
+            ids(&page),
+            ["Session_d", "Session_c", "Session_b", "Session_a"]
+        );
+
+        let by_name = ListQuery {
+            sort: SortKey::Name,
+            ..Default::default()
+        };
+        // "Alpha" and "alpha" tie, so the newer one comes first.
+        assert_eq!(
+            ids(&select(library(), &by_name)),
+            ["Session_d", "Session_a", "Session_c", "Session_b"]
+        );
+
+        let shortest_first = ListQuery {
+            sort: SortKey::Duration,
+            ascending: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&select(library(), &shortest_first)),
+            ["Session_c", "Session_d", "Session_a", "Session_b"]
+        );
+    }
+
+    #[test]
+    fn pages_after_filtering_and_sorting() {
+        let query = ListQuery {
+            offset: 1,
+            limit: Some(2),
+            ..Default::default()
+        };
+        let page = select(library(), &query);
+        assert_eq!(ids(&page), ["Session_c", "Session_b"]);
+        assert_eq!((page.total, page.offset), (4, 1));
+
+        let past_the_end = ListQuery {
+            offset: 10,
+            ..Default::default()
+        };
+        let page = select(library(), &past_the_end);
+        assert!(page.items.is_empty());
+        assert_eq!(page.total, 4);
+    }
+}
@@ -0,0 +1,321 @@
+use crate::chapters::Marker;
+use crate::edit::EditRecord;
+use crate::media::MediaInfo;
+use crate::silence::SilenceAnalysis;
+use crate::RecordingOptions;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+const LEGACY_METADATA_FILE: &str = "metadata.json";
+
+/// Bump together with a new step in `migrate` whenever the layout changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Everything Reframe knows about a session folder. Written by the backend
+/// when a recording starts and finalized when it stops.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionManifest {
+    pub schema_version: u32,
+    pub app_version: String,
+    pub name: String,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: u64,
+    pub duration_ms: u64,
+    pub video_file: String,
+    /// Part files of a split recording in order, starting with `video_file`.
+    /// Empty for recordings made as a single file.
+    #[serde(default)]
+    pub parts: Vec<String>,
+    pub options: Option<RecordingOptions>,
+    pub media: Option<MediaInfo>,
+    #[serde(default)]
+    pub markers: Vec<Marker>,
+    /// Trimmed/cut versions derived from `video_file`, oldest first.
+    #[serde(default)]
+    pub edits: Vec<EditRecord>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub favorite: bool,
+    /// Latest `detect_silence` result for `video_file`.
+    #[serde(default)]
+    pub silence: Option<SilenceAnalysis>,
+}
+
+impl SessionManifest {
+    /// `video_file` is the recording's file name inside the session folder.
+    pub fn new(name: String, video_file: String, options: Option<RecordingOptions>) -> Self {
+        SessionManifest {
+            schema_version: SCHEMA_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            name,
+            created_at: chrono::Local::now().timestamp_millis() as u64,
+            duration_ms: 0,
+            video_file,
+            parts: Vec::new(),
+            options,
+            media: None,
+            markers: Vec::new(),
+            edits: Vec::new(),
+            tags: Vec::new(),
+            notes: String::new(),
+            favorite: false,
+            silence: None,
+        }
+    }
+}
+
+/// Trims tags, drops empty ones and removes case-insensitive duplicates,
+/// keeping the first spelling.
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+/// Shape of the `metadata.json` the dashboard used to write after stopping.
+#[derive(Deserialize)]
+struct LegacyMetadata {
+    name: String,
+    duration: String,
+    timestamp: u64,
+    #[serde(default)]
+    markers: Vec<Marker>,
+}
+
+// "MM:SS" or "H:MM:SS", as produced by the dashboard's formatTime.
+fn parse_clock(text: &str) -> u64 {
+    text.split(':')
+        .map(|part| part.trim().parse::<u64>().unwrap_or(0))
+        .fold(0, |acc, part| acc * 60 + part)
+        * 1000
+}
+
+fn from_legacy(legacy: LegacyMetadata) -> SessionManifest {
+    // The dashboard always recorded a single MP4.
+    let mut manifest = SessionManifest::new(legacy.name, "screen.mp4".into(), None);
+    manifest.created_at = legacy.timestamp;
+    manifest.duration_ms = parse_clock(&legacy.duration);
+    manifest.markers = legacy.markers;
+    manifest.app_version = String::new();
+    manifest
+}
+
+// Upgrades a manifest document one schema version at a time.
+fn migrate(mut doc: Value) -> Result<Value, String> {
+    let version = doc
+        .get("schemaVersion")
+        .and_then(Value::as_u64)
+        .ok_or("Manifest has no schema version")? as u32;
+
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "Manifest schema {} is newer than supported ({})",
+            version, SCHEMA_VERSION
+        ));
+    }
+
+    // Future migrations go here, e.g. `if version < 2 { ... }`.
+
+    doc["schemaVersion"] = Value::from(SCHEMA_VERSION);
+    Ok(doc)
+}
+
+// Held for every read-modify-write of a manifest, so a job finishing and a
+// rename or tag made meanwhile don't overwrite each other's changes.
+static WRITES: Mutex<()> = Mutex::new(());
+
+pub fn save(session_dir: &Path, manifest: &SessionManifest) -> Result<(), String> {
+    let _guard = WRITES.lock().unwrap();
+    write(session_dir, manifest)
+}
+
+fn write(session_dir: &Path, manifest: &SessionManifest) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+    // Write then rename so a crash never leaves a truncated manifest behind.
+    let temp_path = session_dir.join(format!("{}.tmp", MANIFEST_FILE));
+    fs::write(&temp_path, json).map_err(|e| format!("Failed to write manifest: {}", e))?;
+    fs::rename(&temp_path, session_dir.join(MANIFEST_FILE))
+        .map_err(|e| format!("Failed to write manifest: {}", e))
+}
+
+/// Loads the manifest of a session folder, upgrading older schemas and
+/// converting a legacy `metadata.json` in place. Returns `Ok(None)` for
+/// folders that have neither.
+pub fn load(session_dir: &Path) -> Result<Option<SessionManifest>, String> {
+    let _guard = WRITES.lock().unwrap();
+    read(session_dir)
+}
+
+/// Reloads the manifest, applies `change` and saves it under one lock, so
+/// long jobs can record their results without losing edits made while they
+/// ran. `fallback` stands in for folders without a manifest.
+pub fn update(
+    session_dir: &Path,
+    fallback: impl FnOnce() -> SessionManifest,
+    change: impl FnOnce(&mut SessionManifest),
+) -> Result<SessionManifest, String> {
+    let _guard = WRITES.lock().unwrap();
+    let mut manifest = read(session_dir)?.unwrap_or_else(fallback);
+    change(&mut manifest);
+    write(session_dir, &manifest)?;
+    Ok(manifest)
+}
+
+fn read(session_dir: &Path) -> Result<Option<SessionManifest>, String> {
+    let manifest_path = session_dir.join(MANIFEST_FILE);
+    if manifest_path.exists() {
+        let text = fs::read_to_string(&manifest_path).map_err(|e| e.to_string())?;
+        let doc: Value = serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid manifest {}: {}", manifest_path.display(), e))?;
+        let needs_upgrade =
+            doc.get("schemaVersion").and_then(Value::as_u64) != Some(SCHEMA_VERSION as u64);
+
+        let manifest: SessionManifest =
+            serde_json::from_value(migrate(doc)?).map_err(|e| e.to_string())?;
+        if needs_upgrade {
+            write(session_dir, &manifest)?;
+        }
+        return Ok(Some(manifest));
+    }
+
+    let legacy_path = session_dir.join(LEGACY_METADATA_FILE);
+    if legacy_path.exists() {
+        let text = fs::read_to_string(&legacy_path).map_err(|e| e.to_string())?;
+        let legacy: LegacyMetadata = serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid metadata {}: {}", legacy_path.display(), e))?;
+        let manifest = from_legacy(legacy);
+        write(session_dir, &manifest)?;
+        let _ = fs::remove_file(&legacy_path);
+        println!("Migrated {} to {}", legacy_path.display(), MANIFEST_FILE);
+        return Ok(Some(manifest));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_dir(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("reframe-manifest-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn parses_dashboard_clock_strings() {
+        assert_eq!(parse_clock("00:00"), 0);
+        assert_eq!(parse_clock("02:05"), 125_000);
+        assert_eq!(parse_clock("1:02:05"), 3_725_000);
+        assert_eq!(parse_clock(" 3 : 07 "), 187_000);
+        // Unreadable parts count as zero rather than failing the migration.
+        assert_eq!(parse_clock(""), 0);
+        assert_eq!(parse_clock("abc"), 0);
+        assert_eq!(parse_clock("xx:30"), 30_000);
+    }
+
+    #[test]
+    fn converts_legacy_metadata_in_place() {
+        let dir = session_dir("legacy");
+        fs::write(
+            dir.join(LEGACY_METADATA_FILE),
+            r#"{
+                "name": "Standup",
+                "duration": "01:30",
+                "timestamp": 1700000000000,
+                "markers": [{ "timeMs": 5000, "label": "Intro" }]
+            }"#,
+        )
+        .unwrap();
+
+        let manifest = load(&dir).unwrap().unwrap();
+        let legacy_gone = !dir.join(LEGACY_METADATA_FILE).exists();
+        let reloaded = load(&dir).unwrap().unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(manifest.schema_version, SCHEMA_VERSION);
+        assert_eq!(manifest.name, "Standup");
+        assert_eq!(manifest.created_at, 1_700_000_000_000);
+        assert_eq!(manifest.duration_ms, 90_000);
+        assert_eq!(manifest.video_file, "screen.mp4");
+        assert_eq!(manifest.markers.len(), 1);
+        assert!(manifest.app_version.is_empty());
+        assert!(legacy_gone);
+        assert_eq!(reloaded.name, "Standup");
+    }
+
+    #[test]
+    fn legacy_metadata_with_a_bad_clock_still_loads() {
+        let dir = session_dir("bad-clock");
+        fs::write(
+            dir.join(LEGACY_METADATA_FILE),
+            r#"{ "name": "Broken", "duration": "--:--", "timestamp": 1 }"#,
+        )
+        .unwrap();
+        let manifest = load(&dir).unwrap().unwrap();
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(manifest.duration_ms, 0);
+        assert!(manifest.markers.is_empty());
+    }
+
+    #[test]
+    fn upgrades_older_schemas_and_fills_new_fields() {
+        let dir = session_dir("schema");
+        // The first manifests had no parts, edits, tags or silence yet.
+        fs::write(
+            dir.join(MANIFEST_FILE),
+            r#"{
+                "schemaVersion": 0,
+                "appVersion": "0.1.0",
+                "name": "Old",
+                "createdAt": 42,
+                "durationMs": 1000,
+                "videoFile": "screen.mkv",
+                "options": null,
+                "media": null
+            }"#,
+        )
+        .unwrap();
+
+        let manifest = load(&dir).unwrap().unwrap();
+        let saved: Value =
+            serde_json::from_str(&fs::read_to_string(dir.join(MANIFEST_FILE)).unwrap()).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(manifest.schema_version, SCHEMA_VERSION);
+        assert_eq!(manifest.video_file, "screen.mkv");
+        assert!(manifest.parts.is_empty() && manifest.tags.is_empty());
+        assert_eq!(saved["schemaVersion"], SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn rejects_newer_or_unversioned_manifests() {
+        let newer = serde_json::json!({ "schemaVersion": SCHEMA_VERSION + 1 });
+        assert!(migrate(newer).unwrap_err().contains("newer"));
+        assert!(migrate(serde_json::json!({ "name": "x" })).is_err());
+    }
+
+    #[test]
+    fn folders_without_metadata_have_no_manifest() {
+        let dir = session_dir("empty");
+        let loaded = load(&dir);
+        let _ = fs::remove_dir_all(&dir);
+        assert!(matches!(loaded, Ok(None)));
+    }
+}
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::Path;
+
+/// Stream layout of a recorded file as reported by ffprobe. Cached in the
+/// session manifest; `file_size`/`file_modified` tell whether it is stale.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MediaInfo {
+    pub duration_ms: u64,
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+    /// Overall bitrate in bits per second.
+    pub bitrate: u64,
+    pub video_codec: Option<String>,
+    pub tracks: Vec<TrackInfo>,
+    pub file_size: u64,
+    /// Seconds since the Unix epoch.
+    pub file_modified: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackInfo {
+    pub index: u32,
+    pub kind: String,
+    pub codec: String,
+    #[serde(default)]
+    pub channels: Option<u32>,
+    #[serde(default)]
+    pub sample_rate: Option<u32>,
+    #[serde(default)]
+    pub bitrate: Option<u64>,
+}
+
+impl MediaInfo {
+    pub fn audio_tracks(&self) -> impl Iterator<Item = &TrackInfo> {
+        self.tracks.iter().filter(|t| t.kind == "audio")
+    }
+
+    /// True when the probed file hasn't changed since this info was taken.
+    pub fn is_current(&self, path: &Path) -> bool {
+        self.file_size > 0 && file_stamp(path) == Some((self.file_size, self.file_modified))
+    }
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    let modified = meta
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some((meta.len(), modified))
+}
+
+#[derive(Deserialize)]
+struct ProbeOutput {
+    #[serde(default)]
+    streams: Vec<ProbeStream>,
+    format: Option<ProbeFormat>,
+}
+
+// ffprobe reports most numbers as strings in its JSON output.
+#[derive(Deserialize)]
+struct ProbeStream {
+    index: u32,
+    codec_type: Option<String>,
+    codec_name: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    avg_frame_rate: Option<String>,
+    r_frame_rate: Option<String>,
+    channels: Option<u32>,
+    sample_rate: Option<String>,
+    bit_rate: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProbeFormat {
+    duration: Option<String>,
+    bit_rate: Option<String>,
+}
+
+// Frame rates come as rationals like "30000/1001"; "0/0" means unknown.
+fn parse_rate(rate: &str) -> Option<f64> {
+    let (num, den) = rate.split_once('/').unwrap_or((rate, "1"));
+    let (num, den) = (num.parse::<f64>().ok()?, den.parse::<f64>().ok()?);
+    if num > 0.0 && den > 0.0 {
+        Some(num / den)
+    } else {
+        None
+    }
+}
+
+pub fn probe(path: &Path) -> Result<MediaInfo, String> {
+    let output = crate::background_command("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-print_format",
+            "json",
+            "-show_format",
+            "-show_streams",
+        ])
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "ffprobe failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let parsed: ProbeOutput = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Invalid ffprobe output: {}", e))?;
+
+    let (file_size, file_modified) = file_stamp(path).unwrap_or((0, 0));
+    let format = parsed.format.unwrap_or(ProbeFormat {
+        duration: None,
+        bit_rate: None,
+    });
+    let mut info = MediaInfo {
+        duration_ms: format
+            .duration
+            .and_then(|d| d.parse::<f64>().ok())
+            .map(|secs| (secs * 1000.0).round() as u64)
+            .unwrap_or(0),
+        bitrate: format.bit_rate.and_then(|b| b.parse().ok()).unwrap_or(0),
+        file_size,
+        file_modified,
+        ..Default::default()
+    };
+
+    for stream in parsed.streams {
+        let kind = stream.codec_type.unwrap_or_else(|| "data".to_string());
+        let codec = stream.codec_name.unwrap_or_else(|| "unknown".to_string());
+        if kind == "video" && info.video_codec.is_none() {
+            info.video_codec = Some(codec.clone());
+            info.width = stream.width.unwrap_or(0);
+            info.height = stream.height.unwrap_or(0);
+            info.fps = stream
+                .avg_frame_rate
+                .as_deref()
+                .and_then(parse_rate)
+                .or_else(|| stream.r_frame_rate.as_deref().and_then(parse_rate))
+                .unwrap_or(0.0);
+        }
+        info.tracks.push(TrackInfo {
+            index: stream.index,
+            kind,
+            codec,
+            channels: stream.channels,
+            sample_rate: stream.sample_rate.and_then(|r| r.parse().ok()),
+            bitrate: stream.bit_rate.and_then(|b| b.parse().ok()),
+        });
+    }
+
+    Ok(info)
+}
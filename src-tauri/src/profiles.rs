@@ -0,0 +1,351 @@
+use crate::RecordingOptions;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const PROFILES_FILE: &str = "profiles.json";
+/// Bump when the export document changes shape.
+const EXPORT_VERSION: u32 = 1;
+const MAX_NAME_LEN: usize = 64;
+
+/// Named capture settings: target, audio sources, encoder, container,
+/// frame rate, resolution and filters.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingProfile {
+    pub id: String,
+    pub name: String,
+    pub options: RecordingOptions,
+}
+
+/// What `export` writes and `import` reads. Ids are left out; imported
+/// profiles always get fresh ones.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileExport {
+    version: u32,
+    profiles: Vec<ExportedProfile>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedProfile {
+    name: String,
+    options: RecordingOptions,
+}
+
+fn validate(name: &str, options: &RecordingOptions) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name is empty".into());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!(
+            "Profile name is longer than {} characters",
+            MAX_NAME_LEN
+        ));
+    }
+    options
+        .validate()
+        .map_err(|e| format!("Invalid profile \"{}\": {}", name, e))
+}
+
+/// Recording profiles persisted to the app data dir.
+pub struct ProfileStore {
+    profiles: Mutex<Vec<RecordingProfile>>,
+    store_path: PathBuf,
+}
+
+impl ProfileStore {
+    pub fn load(data_dir: &Path) -> Self {
+        let store_path = data_dir.join(PROFILES_FILE);
+        let profiles = match fs::read_to_string(&store_path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                println!("Ignoring unreadable {}: {}", PROFILES_FILE, e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        ProfileStore {
+            profiles: Mutex::new(profiles),
+            store_path,
+        }
+    }
+
+    fn persist(&self, profiles: &[RecordingProfile]) -> Result<(), String> {
+        if let Some(dir) = self.store_path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(profiles).map_err(|e| e.to_string())?;
+        let temp_path = self.store_path.with_extension("json.tmp");
+        fs::write(&temp_path, json).map_err(|e| format!("Failed to save profiles: {}", e))?;
+        fs::rename(&temp_path, &self.store_path)
+            .map_err(|e| format!("Failed to save profiles: {}", e))
+    }
+
+    pub fn list(&self) -> Vec<RecordingProfile> {
+        self.profiles.lock().unwrap().clone()
+    }
+
+    pub fn get(&self, id: &str) -> Result<RecordingProfile, String> {
+        self.profiles
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or_else(|| format!("Profile not found: {}", id))
+    }
+
+    pub fn create(
+        &self,
+        name: &str,
+        options: RecordingOptions,
+    ) -> Result<RecordingProfile, String> {
+        validate(name, &options)?;
+        let mut profiles = self.profiles.lock().unwrap();
+        if profiles
+            .iter()
+            .any(|p| p.name.eq_ignore_ascii_case(name.trim()))
+        {
+            return Err(format!(
+                "A profile named \"{}\" already exists",
+                name.trim()
+            ));
+        }
+        let profile = RecordingProfile {
+            id: new_id(&profiles),
+            name: name.trim().to_string(),
+            options,
+        };
+        let mut next = profiles.clone();
+        next.push(profile.clone());
+        self.persist(&next)?;
+        *profiles = next;
+        Ok(profile)
+    }
+
+    pub fn update(
+        &self,
+        id: &str,
+        name: &str,
+        options: RecordingOptions,
+    ) -> Result<RecordingProfile, String> {
+        validate(name, &options)?;
+        let mut profiles = self.profiles.lock().unwrap();
+        if profiles
+            .iter()
+            .any(|p| p.id != id && p.name.eq_ignore_ascii_case(name.trim()))
+        {
+            return Err(format!(
+                "A profile named \"{}\" already exists",
+                name.trim()
+            ));
+        }
+        let mut next = profiles.clone();
+        let profile = next
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| format!("Profile not found: {}", id))?;
+        profile.name = name.trim().to_string();
+        profile.options = options;
+        let updated = profile.clone();
+        self.persist(&next)?;
+        *profiles = next;
+        Ok(updated)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        let mut profiles = self.profiles.lock().unwrap();
+        if !profiles.iter().any(|p| p.id == id) {
+            return Err(format!("Profile not found: {}", id));
+        }
+        let next: Vec<RecordingProfile> = profiles.iter().filter(|p| p.id != id).cloned().collect();
+        self.persist(&next)?;
+        *profiles = next;
+        Ok(())
+    }
+
+    /// The given profiles, or all of them, as a JSON document for sharing.
+    pub fn export(&self, ids: Option<&[String]>) -> Result<String, String> {
+        let profiles = self.profiles.lock().unwrap();
+        let document = ProfileExport {
+            version: EXPORT_VERSION,
+            profiles: profiles
+                .iter()
+                .filter(|p| ids.is_none_or(|ids| ids.contains(&p.id)))
+                .map(|p| ExportedProfile {
+                    name: p.name.clone(),
+                    options: p.options.clone(),
+                })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&document).map_err(|e| e.to_string())
+    }
+
+    /// Adds the profiles of an exported document. Nothing is added unless
+    /// every profile is valid; names that are taken get a number appended.
+    pub fn import(&self, json: &str) -> Result<Vec<RecordingProfile>, String> {
+        let document: ProfileExport =
+            serde_json::from_str(json).map_err(|e| format!("Invalid profile file: {}", e))?;
+        if document.version > EXPORT_VERSION {
+            return Err(format!(
+                "Profile file version {} is newer than supported ({})",
+                document.version, EXPORT_VERSION
+            ));
+        }
+        for profile in &document.profiles {
+            validate(&profile.name, &profile.options)?;
+        }
+
+        let mut profiles = self.profiles.lock().unwrap();
+        let mut next = profiles.clone();
+        let mut imported = Vec::with_capacity(document.profiles.len());
+        for profile in document.profiles {
+            let name = free_name(&next, profile.name.trim());
+            let profile = RecordingProfile {
+                id: new_id(&next),
+                name,
+                options: profile.options,
+            };
+            next.push(profile.clone());
+            imported.push(profile);
+        }
+        self.persist(&next)?;
+        *profiles = next;
+        Ok(imported)
+    }
+}
+
+fn new_id(profiles: &[RecordingProfile]) -> String {
+    let now = chrono::Local::now().timestamp_millis();
+    let mut n = profiles.len();
+    loop {
+        let id = format!("profile-{}-{}", now, n);
+        if !profiles.iter().any(|p| p.id == id) {
+            return id;
+        }
+        n += 1;
+    }
+}
+
+// `name`, or `name (2)` and up when a profile already has it.
+fn free_name(profiles: &[RecordingProfile], name: &str) -> String {
+    let taken = |candidate: &str| {
+        profiles
+            .iter()
+            .any(|p| p.name.eq_ignore_ascii_case(candidate))
+    };
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while taken(&candidate) {
+        candidate = format!("{} ({})", name, n);
+        n += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(framerate: u32) -> RecordingOptions {
+        serde_json::from_value(serde_json::json!({
+            "micEnabled": false,
+            "systemAudioEnabled": true,
+            "framerate": framerate,
+        }))
+        .unwrap()
+    }
+
+    fn store(name: &str) -> (PathBuf, ProfileStore) {
+        let dir =
+            std::env::temp_dir().join(format!("reframe-profiles-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let store = ProfileStore::load(&dir);
+        (dir, store)
+    }
+
+    #[test]
+    fn rejects_blank_long_and_duplicate_names() {
+        let (dir, store) = store("names");
+        let blank = store.create("  ", options(30));
+        let long = store.create(&"x".repeat(MAX_NAME_LEN + 1), options(30));
+        let first = store.create(" Tutorial ", options(30));
+        let duplicate = store.create("tutorial", options(60));
+        let bad_fps = store.create("Fast", options(500));
+        let listed = store.list();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(blank.is_err());
+        assert!(long.is_err());
+        assert_eq!(first.unwrap().name, "Tutorial");
+        assert!(duplicate.unwrap_err().contains("already exists"));
+        assert!(bad_fps.is_err());
+        assert_eq!(listed.len(), 1);
+    }
+
+    #[test]
+    fn changes_survive_a_reload() {
+        let (dir, store) = store("reload");
+        let kept = store.create("Kept", options(30)).unwrap();
+        let gone = store.create("Gone", options(30)).unwrap();
+        store.update(&kept.id, "Renamed", options(60)).unwrap();
+        store.delete(&gone.id).unwrap();
+        let missing = store.delete(&gone.id);
+        let reloaded = ProfileStore::load(&dir).list();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(missing.is_err());
+        assert_eq!(reloaded.len(), 1);
+        assert_eq!(reloaded[0].id, kept.id);
+        assert_eq!(reloaded[0].name, "Renamed");
+        assert_eq!(reloaded[0].options.framerate, Some(60));
+    }
+
+    #[test]
+    fn import_renames_clashes_and_is_all_or_nothing() {
+        let (dir, store) = store("import");
+        store.create("Demo", options(30)).unwrap();
+        let exported = store.export(None).unwrap();
+        let imported = store.import(&exported).unwrap();
+        let again = store.import(&exported).unwrap();
+
+        let invalid = r#"{"version":1,"profiles":[
+            {"name":"Fine","options":{"micEnabled":false,"systemAudioEnabled":false}},
+            {"name":"","options":{"micEnabled":false,"systemAudioEnabled":false}}
+        ]}"#;
+        let rejected = store.import(invalid);
+        let newer = store.import(r#"{"version":99,"profiles":[]}"#);
+        let names: Vec<String> = store.list().into_iter().map(|p| p.name).collect();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(imported[0].name, "Demo (2)");
+        assert_eq!(again[0].name, "Demo (3)");
+        assert!(rejected.is_err());
+        assert!(newer.unwrap_err().contains("newer"));
+        assert_eq!(names, ["Demo", "Demo (2)", "Demo (3)"]);
+    }
+
+    #[test]
+    fn failed_saves_leave_memory_untouched() {
+        let (dir, _) = store("unwritable");
+        // A file where the data dir should be makes every save fail.
+        fs::write(&dir, "").unwrap();
+        let store = ProfileStore::load(&dir.join("nested"));
+        let created = store.create("Demo", options(30));
+        let imported = store.import(
+            r#"{"version":1,"profiles":[
+                {"name":"Shared","options":{"micEnabled":false,"systemAudioEnabled":false}}
+            ]}"#,
+        );
+        let listed = store.list();
+        let _ = fs::remove_file(&dir);
+
+        assert!(created.is_err());
+        assert!(imported.is_err());
+        assert!(listed.is_empty());
+    }
+}
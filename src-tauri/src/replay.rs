@@ -0,0 +1,155 @@
+use crate::media;
+use crate::RecordingOptions;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Child;
+
+/// Length of each rolling segment. Keyframes come every second, so saved
+/// replays start within a second of the requested length.
+const SEGMENT_SECONDS: u32 = 2;
+pub const DEFAULT_BUFFER_SECONDS: u32 = 120;
+/// Longest buffer we keep, so the temp folder stays bounded.
+pub const MAX_BUFFER_SECONDS: u32 = 30 * 60;
+
+/// A capture writing into rolling segments until it is stopped. FFmpeg
+/// overwrites the oldest segment once the buffer is full.
+pub struct ReplayBuffer {
+    process: Child,
+    dir: PathBuf,
+    pub buffer_seconds: u32,
+    /// Stored in the manifest of every saved replay.
+    pub options: RecordingOptions,
+}
+
+/// Where the buffer keeps its segments; wiped on every start and stop.
+pub fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join("reframe-replay")
+}
+
+/// Output arguments for the rolling segments. MPEG-TS segments stay readable
+/// while FFmpeg is still writing them.
+pub fn output_args(dir: &Path, buffer_seconds: u32) -> Vec<String> {
+    // One spare segment so a full buffer survives the one being rewritten.
+    let segments = buffer_seconds.div_ceil(SEGMENT_SECONDS) + 1;
+    vec![
+        "-f".into(),
+        "segment".into(),
+        "-segment_time".into(),
+        SEGMENT_SECONDS.to_string(),
+        "-segment_wrap".into(),
+        segments.to_string(),
+        "-segment_format".into(),
+        "mpegts".into(),
+        "-reset_timestamps".into(),
+        "1".into(),
+        dir.join("replay_%04d.ts").to_string_lossy().to_string(),
+    ]
+}
+
+impl ReplayBuffer {
+    pub fn new(
+        process: Child,
+        dir: PathBuf,
+        buffer_seconds: u32,
+        options: RecordingOptions,
+    ) -> Self {
+        ReplayBuffer {
+            process,
+            dir,
+            buffer_seconds,
+            options,
+        }
+    }
+
+    /// Ends the capture and deletes its segments.
+    pub fn stop(mut self) {
+        crate::stop_ffmpeg(&mut self.process);
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            println!("Failed to clean up replay buffer: {}", e);
+        }
+    }
+
+    /// False once FFmpeg has exited on its own, e.g. a device disappeared.
+    pub fn is_running(&mut self) -> bool {
+        matches!(self.process.try_wait(), Ok(None))
+    }
+
+    /// Folder FFmpeg writes the segments into.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+// Segments oldest first. Wrapped names get reused, so the write time is
+// what orders them.
+fn segments(dir: &Path) -> Vec<PathBuf> {
+    let Ok(dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut segments: Vec<(std::time::SystemTime, PathBuf)> = dir
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "ts"))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    segments.sort();
+    segments.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Joins the most recent `seconds` of the buffer into `target` without
+/// re-encoding.
+pub fn save(dir: &Path, seconds: u32, target: &Path) -> Result<(), String> {
+    let mut picked = Vec::new();
+    let mut covered_ms = 0;
+    for segment in segments(dir).into_iter().rev() {
+        // The segment being written may not have a readable duration yet.
+        let Ok(info) = media::probe(&segment) else {
+            continue;
+        };
+        if info.duration_ms == 0 {
+            continue;
+        }
+        covered_ms += info.duration_ms;
+        picked.push(segment);
+        if covered_ms >= seconds as u64 * 1000 {
+            break;
+        }
+    }
+    if picked.is_empty() {
+        return Err("The replay buffer is still empty".into());
+    }
+    picked.reverse();
+
+    // Start the oldest segment late enough to keep exactly `seconds`.
+    let skip_ms = covered_ms.saturating_sub(seconds as u64 * 1000);
+    let mut list = String::new();
+    for (i, segment) in picked.iter().enumerate() {
+        let path = segment
+            .to_string_lossy()
+            .replace('\\', "/")
+            .replace('\'', "'\\''");
+        list.push_str(&format!("file '{}'\n", path));
+        if i == 0 && skip_ms > 0 {
+            list.push_str(&format!("inpoint {:.3}\n", skip_ms as f64 / 1000.0));
+        }
+    }
+    // Next to the target, so saves running side by side never share a list.
+    let list_path = target.with_file_name("concat.txt");
+    fs::write(&list_path, list).map_err(|e| format!("Failed to write concat list: {}", e))?;
+
+    let result = crate::run_ffmpeg([
+        "-y".to_string(),
+        "-f".into(),
+        "concat".into(),
+        "-safe".into(),
+        "0".into(),
+        "-i".into(),
+        list_path.to_string_lossy().to_string(),
+        "-c".into(),
+        "copy".into(),
+        "-movflags".into(),
+        "+faststart".into(),
+        target.to_string_lossy().to_string(),
+    ]);
+    let _ = fs::remove_file(&list_path);
+    result
+}
@@ -0,0 +1,399 @@
+use crate::manifest;
+use crate::trash;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where `archive` moves sessions to, inside the save folder. The library
+/// skips it because its name doesn't start with `Session_`.
+pub const ARCHIVE_DIR: &str = "Archive";
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// What happens to recordings retention removes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum RetentionAction {
+    /// Move to the trash, which purges it after its own retention period.
+    #[default]
+    Trash,
+    Archive,
+}
+
+/// Cleanup rules from the `retention` setting. Every limit is optional;
+/// favorites and the newest recording are never removed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionPolicy {
+    /// Keep only this many of the newest recordings.
+    pub keep_last: Option<usize>,
+    /// Remove the oldest recordings until the library fits in this many GB.
+    /// The trash and archive don't count; the trash is purged on its own.
+    pub max_total_gb: Option<f64>,
+    /// Remove recordings older than this.
+    pub max_age_days: Option<u64>,
+    pub action: RetentionAction,
+}
+
+impl RetentionPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.keep_last.is_some_and(|n| n > 0)
+            || self.max_total_gb.is_some_and(|gb| gb > 0.0)
+            || self.max_age_days.is_some_and(|days| days > 0)
+    }
+}
+
+#[derive(Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionCandidate {
+    pub id: String,
+    pub name: String,
+    pub created_at: u64,
+    pub size_bytes: u64,
+    /// "count", "age" or "size": the first rule the recording falls foul of.
+    pub reason: &'static str,
+}
+
+struct Session {
+    id: String,
+    name: String,
+    created_at: u64,
+    size_bytes: u64,
+    favorite: bool,
+}
+
+// Sessions retention may remove, newest first, and the bytes of the other
+// sessions the size limit counts: skipped ones and those whose manifest
+// can't be read.
+fn scan(root: &Path, skip: &[PathBuf]) -> (Vec<Session>, u64) {
+    let Ok(entries) = fs::read_dir(root) else {
+        return (Vec::new(), 0);
+    };
+    let mut sessions = Vec::new();
+    let mut other_bytes = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let id = entry.file_name().to_string_lossy().to_string();
+        if !path.is_dir() || !id.starts_with("Session_") {
+            continue;
+        }
+        let size_bytes = crate::dir_size(&path);
+        // Compared by name: job paths may be spelled differently than `root`.
+        if skip
+            .iter()
+            .any(|p| p.file_name() == Some(entry.file_name().as_os_str()))
+        {
+            other_bytes += size_bytes;
+            continue;
+        }
+        let session = match manifest::load(&path) {
+            Ok(Some(manifest)) => Session {
+                id,
+                name: manifest.name,
+                created_at: manifest.created_at,
+                size_bytes,
+                favorite: manifest.favorite,
+            },
+            // Bare folders, e.g. when the recording couldn't be probed.
+            Ok(None) => Session {
+                name: id.clone(),
+                id,
+                created_at: crate::created_millis(&path),
+                size_bytes,
+                favorite: false,
+            },
+            // It might be a favorite, so it is never removed.
+            Err(e) => {
+                println!("Retention keeps {}: {}", id, e);
+                other_bytes += size_bytes;
+                continue;
+            }
+        };
+        sessions.push(session);
+    }
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+    (sessions, other_bytes)
+}
+
+/// What enforcing `policy` would remove, oldest first. `skip` lists sessions
+/// that are in use: the one being recorded and those with pending jobs.
+pub fn plan(root: &Path, policy: &RetentionPolicy, skip: &[PathBuf]) -> Vec<RetentionCandidate> {
+    if !policy.is_enabled() {
+        return Vec::new();
+    }
+    let (sessions, other_bytes) = scan(root, skip);
+    let now = chrono::Local::now().timestamp_millis() as u64;
+    select(sessions, other_bytes, policy, now)
+}
+
+// `plan` for sessions sorted newest first.
+fn select(
+    sessions: Vec<Session>,
+    other_bytes: u64,
+    policy: &RetentionPolicy,
+    now: u64,
+) -> Vec<RetentionCandidate> {
+    let max_bytes = policy
+        .max_total_gb
+        .filter(|gb| *gb > 0.0)
+        .map(|gb| (gb * GB) as u64);
+
+    // Walk newest to oldest counting kept recordings, then let the size
+    // limit claim the oldest survivors.
+    let mut reasons: Vec<Option<&'static str>> = Vec::with_capacity(sessions.len());
+    let mut kept = 0;
+    for (i, session) in sessions.iter().enumerate() {
+        // The newest is usually the one that just stopped; never lose it.
+        let reason = if session.favorite || i == 0 {
+            None
+        } else if policy.keep_last.is_some_and(|n| n > 0 && kept >= n) {
+            Some("count")
+        } else if policy
+            .max_age_days
+            .is_some_and(|days| days > 0 && now.saturating_sub(session.created_at) > days * DAY_MS)
+        {
+            Some("age")
+        } else {
+            None
+        };
+        if reason.is_none() && !session.favorite {
+            kept += 1;
+        }
+        reasons.push(reason);
+    }
+    if let Some(max_bytes) = max_bytes {
+        let mut total_bytes: u64 = other_bytes
+            + sessions
+                .iter()
+                .zip(&reasons)
+                .filter(|(_, reason)| reason.is_none())
+                .map(|(s, _)| s.size_bytes)
+                .sum::<u64>();
+        for (session, reason) in sessions.iter().zip(reasons.iter_mut()).skip(1).rev() {
+            if total_bytes <= max_bytes {
+                break;
+            }
+            if !session.favorite && reason.is_none() {
+                *reason = Some("size");
+                total_bytes = total_bytes.saturating_sub(session.size_bytes);
+            }
+        }
+    }
+
+    sessions
+        .into_iter()
+        .zip(reasons)
+        .rev()
+        .filter_map(|(session, reason)| {
+            Some(RetentionCandidate {
+                reason: reason?,
+                id: session.id,
+                name: session.name,
+                created_at: session.created_at,
+                size_bytes: session.size_bytes,
+            })
+        })
+        .collect()
+}
+
+fn archive(root: &Path, session_dir: &Path, id: &str) -> Result<PathBuf, String> {
+    let archive_dir = root.join(ARCHIVE_DIR);
+    fs::create_dir_all(&archive_dir).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let target = trash::free_name(&archive_dir, id);
+    fs::rename(session_dir, &target).map_err(|e| format!("Failed to archive {}: {}", id, e))?;
+    Ok(target)
+}
+
+/// Enforces `policy`, returning what was removed. Failures are logged and
+/// skipped so one locked folder doesn't stop the rest.
+pub fn enforce(root: &Path, policy: &RetentionPolicy, skip: &[PathBuf]) -> Vec<RetentionCandidate> {
+    plan(root, policy, skip)
+        .into_iter()
+        .filter(|candidate| {
+            let session_dir = root.join(&candidate.id);
+            let result = match policy.action {
+                RetentionAction::Trash => trash::move_to_trash(&session_dir).map(|_| ()),
+                RetentionAction::Archive => archive(root, &session_dir, &candidate.id).map(|_| ()),
+            };
+            match result {
+                Ok(()) => {
+                    println!("Retention removed {} ({})", candidate.id, candidate.reason);
+                    true
+                }
+                Err(e) => {
+                    println!("Retention failed for {}: {}", candidate.id, e);
+                    false
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::SessionManifest;
+
+    const NOW: u64 = 1_000 * DAY_MS;
+    const MB: u64 = 1024 * 1024;
+
+    // Session `n` was made `n` days ago, so lower numbers are newer.
+    fn session(n: u64, size_mb: u64, favorite: bool) -> Session {
+        Session {
+            id: format!("Session_{}", n),
+            name: format!("Recording {}", n),
+            created_at: NOW - n * DAY_MS,
+            size_bytes: size_mb * MB,
+            favorite,
+        }
+    }
+
+    fn removed(candidates: &[RetentionCandidate]) -> Vec<(&str, &str)> {
+        candidates
+            .iter()
+            .map(|c| (c.id.as_str(), c.reason))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_the_newest_recordings() {
+        let policy = RetentionPolicy {
+            keep_last: Some(2),
+            ..Default::default()
+        };
+        let sessions = (0..4).map(|n| session(n, 1, false)).collect();
+        assert_eq!(
+            removed(&select(sessions, 0, &policy, NOW)),
+            [("Session_3", "count"), ("Session_2", "count")]
+        );
+    }
+
+    #[test]
+    fn removes_recordings_past_the_age_limit() {
+        let policy = RetentionPolicy {
+            max_age_days: Some(30),
+            ..Default::default()
+        };
+        let sessions = [1, 30, 31, 90].map(|n| session(n, 1, false)).into();
+        assert_eq!(
+            removed(&select(sessions, 0, &policy, NOW)),
+            [("Session_90", "age"), ("Session_31", "age")]
+        );
+    }
+
+    #[test]
+    fn size_limit_removes_the_oldest_and_counts_skipped_sessions() {
+        let policy = RetentionPolicy {
+            max_total_gb: Some(1.0),
+            ..Default::default()
+        };
+        let sessions: Vec<Session> = (0..4).map(|n| session(n, 300, false)).collect();
+        // 1200 MB of recordings: dropping the oldest gets under 1 GB.
+        assert_eq!(
+            removed(&select(sessions, 0, &policy, NOW)),
+            [("Session_3", "size")]
+        );
+        // With a 300 MB recording in progress it takes two.
+        let sessions = (0..4).map(|n| session(n, 300, false)).collect();
+        assert_eq!(
+            removed(&select(sessions, 300 * MB, &policy, NOW)),
+            [("Session_3", "size"), ("Session_2", "size")]
+        );
+    }
+
+    #[test]
+    fn size_limit_counts_what_other_rules_remove() {
+        let policy = RetentionPolicy {
+            keep_last: Some(3),
+            max_total_gb: Some(0.5),
+            ..Default::default()
+        };
+        let sessions = (0..4).map(|n| session(n, 300, false)).collect();
+        assert_eq!(
+            removed(&select(sessions, 0, &policy, NOW)),
+            [
+                ("Session_3", "count"),
+                ("Session_2", "size"),
+                ("Session_1", "size")
+            ]
+        );
+    }
+
+    #[test]
+    fn always_keeps_the_newest_recording() {
+        let policy = RetentionPolicy {
+            max_total_gb: Some(0.1),
+            max_age_days: Some(1),
+            ..Default::default()
+        };
+        let sessions = [5, 10].map(|n| session(n, 300, false)).into();
+        assert_eq!(
+            removed(&select(sessions, 0, &policy, NOW)),
+            [("Session_10", "age")]
+        );
+    }
+
+    #[test]
+    fn never_removes_favorites() {
+        let policy = RetentionPolicy {
+            keep_last: Some(1),
+            max_total_gb: Some(0.5),
+            max_age_days: Some(1),
+            ..Default::default()
+        };
+        let sessions = vec![
+            session(0, 400, false),
+            session(5, 400, true),
+            session(10, 400, false),
+        ];
+        // Favorites don't use up the kept count either, and the newest
+        // stays even though the library is still over the limit.
+        assert_eq!(
+            removed(&select(sessions, 0, &policy, NOW)),
+            [("Session_10", "count")]
+        );
+    }
+
+    #[test]
+    fn disabled_policy_removes_nothing() {
+        let root = std::env::temp_dir().join("reframe-retention-disabled-missing");
+        assert!(plan(&root, &RetentionPolicy::default(), &[]).is_empty());
+    }
+
+    #[test]
+    fn skips_sessions_in_use_and_lists_bare_folders() {
+        let root = std::env::temp_dir().join(format!("reframe-retention-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let sessions = [
+            ("Session_old", Some(0)),
+            ("Session_bare", None),
+            ("Session_active", Some(u64::MAX / 2)),
+            ("Session_newest", Some(u64::MAX / 4)),
+            ("Session_busy", Some(1)),
+        ];
+        for (id, created_at) in sessions {
+            let dir = root.join(id);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("screen.mp4"), b"video").unwrap();
+            if let Some(created_at) = created_at {
+                let mut session_manifest =
+                    SessionManifest::new(id.into(), "screen.mp4".into(), None);
+                session_manifest.created_at = created_at;
+                manifest::save(&dir, &session_manifest).unwrap();
+            }
+        }
+
+        let policy = RetentionPolicy {
+            keep_last: Some(1),
+            ..Default::default()
+        };
+        let skip = [root.join("Session_active"), root.join("Session_busy")];
+        let candidates = plan(&root, &policy, &skip);
+        let _ = fs::remove_dir_all(&root);
+        assert_eq!(
+            removed(&candidates),
+            [("Session_old", "count"), ("Session_bare", "count")]
+        );
+    }
+}
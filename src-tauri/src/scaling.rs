@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// How a capture is fitted into the requested output size. The source size
+/// isn't known before FFmpeg opens it, so every mode is expressed in filter
+/// expressions that adapt to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ScaleMode {
+    /// Scale to fit inside the size and letterbox the rest in black.
+    Fit,
+    /// Scale to cover the size and crop what overflows.
+    Fill,
+    /// Shrink to fit inside the size but never enlarge; the output keeps the
+    /// source's aspect ratio instead of being padded.
+    FitWithin,
+    /// Stretch to exactly the size, ignoring the aspect ratio.
+    Exact,
+}
+
+impl ScaleMode {
+    /// The mode used when none is chosen. Full screens are letterboxed to
+    /// exactly the preset size; windows and regions are usually smaller and
+    /// oddly shaped, so they are only ever shrunk.
+    pub fn for_capture(capture_mode: Option<&str>) -> ScaleMode {
+        match capture_mode {
+            Some("window") | Some("region") => ScaleMode::FitWithin,
+            _ => ScaleMode::Fit,
+        }
+    }
+}
+
+/// Trims a native-size capture to even dimensions; windows and regions can
+/// have odd ones, which the encoders reject.
+pub const EVEN_CROP: &str = "crop=trunc(iw/2)*2:trunc(ih/2)*2";
+
+/// Rounds down to the even sizes H.264 with 4:2:0 chroma requires.
+fn even(n: u32) -> u32 {
+    (n & !1).max(2)
+}
+
+/// Video filters producing a frame of at most `width`x`height` (rounded
+/// down to even) from a source of any size.
+pub fn filters(mode: ScaleMode, width: u32, height: u32) -> Vec<String> {
+    let (w, h) = (even(width), even(height));
+    let mut filters = match mode {
+        ScaleMode::Fit => vec![
+            format!(
+                "scale={}:{}:force_original_aspect_ratio=decrease:force_divisible_by=2:flags=lanczos",
+                w, h
+            ),
+            format!("pad={}:{}:(ow-iw)/2:(oh-ih)/2:color=black", w, h),
+        ],
+        ScaleMode::Fill => vec![
+            format!(
+                "scale={}:{}:force_original_aspect_ratio=increase:flags=lanczos",
+                w, h
+            ),
+            format!("crop={}:{}", w, h),
+        ],
+        ScaleMode::FitWithin => vec![format!(
+            "scale='min({},iw)':'min({},ih)':force_original_aspect_ratio=decrease:force_divisible_by=2:flags=lanczos",
+            w, h
+        )],
+        ScaleMode::Exact => vec![format!("scale={}:{}:flags=lanczos", w, h)],
+    };
+    // Square pixels, so players don't stretch the frame back.
+    filters.push("setsar=1".into());
+    filters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The WxH every size-setting filter in the chain asks for.
+    fn requested_sizes(filters: &[String]) -> Vec<(u32, u32)> {
+        filters
+            .iter()
+            .filter_map(|f| {
+                let (name, args) = f.split_once('=')?;
+                if !["scale", "pad", "crop"].contains(&name) {
+                    return None;
+                }
+                let mut parts = args.split(':');
+                Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn picks_a_mode_by_capture() {
+        assert_eq!(ScaleMode::for_capture(None), ScaleMode::Fit);
+        assert_eq!(ScaleMode::for_capture(Some("fullscreen")), ScaleMode::Fit);
+        assert_eq!(ScaleMode::for_capture(Some("window")), ScaleMode::FitWithin);
+        assert_eq!(ScaleMode::for_capture(Some("region")), ScaleMode::FitWithin);
+    }
+
+    #[test]
+    fn fit_letterboxes_to_the_exact_size() {
+        assert_eq!(
+            filters(ScaleMode::Fit, 1920, 1080),
+            [
+                "scale=1920:1080:force_original_aspect_ratio=decrease:force_divisible_by=2:flags=lanczos",
+                "pad=1920:1080:(ow-iw)/2:(oh-ih)/2:color=black",
+                "setsar=1",
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_crops_the_overflow() {
+        assert_eq!(
+            filters(ScaleMode::Fill, 1080, 1920),
+            [
+                "scale=1080:1920:force_original_aspect_ratio=increase:flags=lanczos",
+                "crop=1080:1920",
+                "setsar=1",
+            ]
+        );
+    }
+
+    #[test]
+    fn fit_within_never_enlarges() {
+        assert_eq!(
+            filters(ScaleMode::FitWithin, 1280, 720),
+            [
+                "scale='min(1280,iw)':'min(720,ih)':force_original_aspect_ratio=decrease:force_divisible_by=2:flags=lanczos",
+                "setsar=1",
+            ]
+        );
+    }
+
+    #[test]
+    fn exact_stretches() {
+        assert_eq!(
+            filters(ScaleMode::Exact, 640, 480),
+            ["scale=640:480:flags=lanczos", "setsar=1"]
+        );
+    }
+
+    #[test]
+    fn odd_sizes_are_rounded_down_to_even() {
+        for mode in [ScaleMode::Fit, ScaleMode::Fill, ScaleMode::Exact] {
+            let sizes = requested_sizes(&filters(mode, 1281, 721));
+            assert!(!sizes.is_empty(), "{:?}", mode);
+            assert!(
+                sizes.iter().all(|&size| size == (1280, 720)),
+                "{:?}: {:?}",
+                mode,
+                sizes
+            );
+        }
+        let fit_within = filters(ScaleMode::FitWithin, 1281, 721);
+        assert!(fit_within[0].starts_with("scale='min(1280,iw)':'min(720,ih)'"));
+        // Sizes the scaler can't shrink further still come out even.
+        assert_eq!(requested_sizes(&filters(ScaleMode::Exact, 1, 1)), [(2, 2)]);
+    }
+
+    #[test]
+    fn adaptive_sizes_are_forced_even() {
+        // Without an exact size, the scaler or crop has to round for us.
+        assert!(filters(ScaleMode::Fit, 1920, 1080)[0].contains("force_divisible_by=2"));
+        assert!(filters(ScaleMode::FitWithin, 1920, 1080)[0].contains("force_divisible_by=2"));
+    }
+}
@@ -0,0 +1,428 @@
+use crate::chapters::Marker;
+use crate::manifest::{self, MANIFEST_FILE};
+use crate::transcribe::{self, TranscriptSegment, TRANSCRIPT_FILE};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Lives in the save folder so it follows the library when it is moved.
+pub const INDEX_FILE: &str = ".reframe-index.json";
+const INDEX_VERSION: u32 = 1;
+
+const NAME_WEIGHT: f64 = 10.0;
+const TAG_WEIGHT: f64 = 8.0;
+const MARKER_WEIGHT: f64 = 5.0;
+const NOTES_WEIGHT: f64 = 3.0;
+const TRANSCRIPT_WEIGHT: f64 = 1.0;
+/// Matches reported per recording; scoring still counts all of them.
+const MAX_MATCHES: usize = 20;
+const SNIPPET_CHARS: usize = 120;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexedSession {
+    /// Modification times of the manifest and transcript when indexed.
+    stamp: (u64, u64),
+    name: String,
+    created_at: u64,
+    tags: Vec<String>,
+    notes: String,
+    markers: Vec<Marker>,
+    transcript: Vec<TranscriptSegment>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchIndex {
+    version: u32,
+    /// Keyed by session folder name.
+    sessions: HashMap<String, IndexedSession>,
+}
+
+#[derive(Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchMatch {
+    /// "name", "tag", "marker", "notes" or "transcript".
+    pub field: &'static str,
+    pub text: String,
+    /// Position inside the recording for marker and transcript hits.
+    pub time_ms: Option<u64>,
+}
+
+#[derive(Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub folder: String,
+    pub path: String,
+    pub name: String,
+    pub score: f64,
+    pub matches: Vec<SearchMatch>,
+}
+
+fn modified_millis(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn session_stamp(session_dir: &Path) -> (u64, u64) {
+    (
+        modified_millis(&session_dir.join(MANIFEST_FILE)),
+        modified_millis(&session_dir.join(TRANSCRIPT_FILE)),
+    )
+}
+
+fn index_session(session_dir: &Path, stamp: (u64, u64)) -> Option<IndexedSession> {
+    let manifest = manifest::load(session_dir).ok()??;
+    Some(IndexedSession {
+        stamp,
+        name: manifest.name,
+        created_at: manifest.created_at,
+        tags: manifest.tags,
+        notes: manifest.notes,
+        markers: manifest.markers,
+        transcript: transcribe::load(session_dir)
+            .map(|t| t.segments)
+            .unwrap_or_default(),
+    })
+}
+
+fn load_index(root: &Path) -> SearchIndex {
+    fs::read_to_string(root.join(INDEX_FILE))
+        .ok()
+        .and_then(|text| serde_json::from_str::<SearchIndex>(&text).ok())
+        .filter(|index| index.version == INDEX_VERSION)
+        .unwrap_or(SearchIndex {
+            version: INDEX_VERSION,
+            sessions: HashMap::new(),
+        })
+}
+
+// Re-reads only the sessions whose manifest or transcript changed since they
+// were indexed and drops the ones that are gone.
+fn refresh_index(root: &Path) -> Result<SearchIndex, String> {
+    let mut index = load_index(root);
+    let mut changed = false;
+    let mut seen = HashSet::new();
+
+    for entry in fs::read_dir(root).map_err(|e| e.to_string())?.flatten() {
+        let path = entry.path();
+        let folder = entry.file_name().to_string_lossy().to_string();
+        if !path.is_dir() || !folder.starts_with("Session_") {
+            continue;
+        }
+        let stamp = session_stamp(&path);
+        seen.insert(folder.clone());
+        if index
+            .sessions
+            .get(&folder)
+            .is_some_and(|s| s.stamp == stamp)
+        {
+            continue;
+        }
+        // Folders without a manifest yet stay unindexed without forcing a
+        // rewrite on every search.
+        changed |= match index_session(&path, stamp) {
+            Some(session) => {
+                index.sessions.insert(folder, session);
+                true
+            }
+            None => index.sessions.remove(&folder).is_some(),
+        };
+    }
+
+    let before = index.sessions.len();
+    index.sessions.retain(|folder, _| seen.contains(folder));
+    changed |= index.sessions.len() != before;
+
+    if changed {
+        match serde_json::to_string(&index) {
+            Ok(json) => {
+                if let Err(e) = fs::write(root.join(INDEX_FILE), json) {
+                    println!("Failed to write search index: {}", e);
+                }
+            }
+            Err(e) => println!("Failed to serialize search index: {}", e),
+        }
+    }
+    Ok(index)
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+// Every query term has to prefix-match some word of the text, so "rec"
+// finds "Recording" but "rec demo" needs both.
+fn matches_all(text: &str, terms: &[String]) -> bool {
+    let words = tokenize(text);
+    terms
+        .iter()
+        .all(|term| words.iter().any(|w| w.starts_with(term.as_str())))
+}
+
+fn snippet(text: &str) -> String {
+    if text.chars().count() <= SNIPPET_CHARS {
+        text.to_string()
+    } else {
+        let cut: String = text.chars().take(SNIPPET_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    }
+}
+
+fn score_session(
+    session: &IndexedSession,
+    terms: &[String],
+    phrase: &str,
+) -> (f64, Vec<SearchMatch>) {
+    let mut score = 0.0;
+    let mut matches = Vec::new();
+    let mut hit = |weight: f64, field, text: &str, time_ms| {
+        score += weight;
+        matches.push(SearchMatch {
+            field,
+            text: snippet(text),
+            time_ms,
+        });
+    };
+
+    if matches_all(&session.name, terms) {
+        // An exact phrase in the title beats scattered words.
+        let bonus = if session.name.to_lowercase().contains(phrase) {
+            NAME_WEIGHT
+        } else {
+            0.0
+        };
+        hit(NAME_WEIGHT + bonus, "name", &session.name, None);
+    }
+    for tag in session.tags.iter().filter(|t| matches_all(t, terms)) {
+        hit(TAG_WEIGHT, "tag", tag, None);
+    }
+    for marker in session
+        .markers
+        .iter()
+        .filter(|m| matches_all(&m.label, terms))
+    {
+        hit(MARKER_WEIGHT, "marker", &marker.label, Some(marker.time_ms));
+    }
+    if matches_all(&session.notes, terms) {
+        hit(NOTES_WEIGHT, "notes", &session.notes, None);
+    }
+    for segment in session
+        .transcript
+        .iter()
+        .filter(|s| matches_all(&s.text, terms))
+    {
+        hit(
+            TRANSCRIPT_WEIGHT,
+            "transcript",
+            &segment.text,
+            Some(segment.start_ms),
+        );
+    }
+
+    matches.truncate(MAX_MATCHES);
+    (score, matches)
+}
+
+/// Searches the library under `root`, refreshing the on-disk index first.
+/// Results are ordered by score, newest first on ties.
+pub fn search(root: &Path, query: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
+    let terms = tokenize(query);
+    if terms.is_empty() || !root.exists() {
+        return Ok(Vec::new());
+    }
+    let phrase = query.trim().to_lowercase();
+    let index = refresh_index(root)?;
+
+    let mut scored: Vec<(u64, SearchResult)> = index
+        .sessions
+        .iter()
+        .filter_map(|(folder, session)| {
+            let (score, matches) = score_session(session, &terms, &phrase);
+            (score > 0.0).then(|| {
+                (
+                    session.created_at,
+                    SearchResult {
+                        folder: folder.clone(),
+                        path: root.join(folder).to_string_lossy().to_string(),
+                        name: session.name.clone(),
+                        score,
+                        matches,
+                    },
+                )
+            })
+        })
+        .collect();
+    scored.sort_by(|(a_time, a), (b_time, b)| {
+        b.score.total_cmp(&a.score).then_with(|| b_time.cmp(a_time))
+    });
+
+    Ok(scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, result)| result)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::SessionManifest;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn terms(query: &str) -> Vec<String> {
+        tokenize(query)
+    }
+
+    fn indexed(name: &str, created_at: u64) -> IndexedSession {
+        IndexedSession {
+            stamp: (0, 0),
+            name: name.into(),
+            created_at,
+            tags: Vec::new(),
+            notes: String::new(),
+            markers: Vec::new(),
+            transcript: Vec::new(),
+        }
+    }
+
+    // Saves a manifest and pins its modification time, so stamps differ
+    // between writes even on filesystems with coarse timestamps.
+    fn write_session(root: &Path, folder: &str, name: &str, created_at: u64, mtime_s: u64) {
+        let dir = root.join(folder);
+        fs::create_dir_all(&dir).unwrap();
+        let mut manifest = SessionManifest::new(name.into(), "screen.mp4".into(), None);
+        manifest.created_at = created_at;
+        manifest::save(&dir, &manifest).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(dir.join(MANIFEST_FILE))
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(mtime_s))
+            .unwrap();
+    }
+
+    fn temp_root(name: &str) -> std::path::PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("reframe-search-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn tokenizes_on_punctuation_and_lowercases() {
+        assert_eq!(
+            tokenize("Sprint-Demo_2, Café  notes!"),
+            ["sprint", "demo", "2", "café", "notes"]
+        );
+        assert!(tokenize(" -- ").is_empty());
+    }
+
+    #[test]
+    fn every_term_must_prefix_a_word() {
+        assert!(matches_all("Recording of the demo", &terms("rec")));
+        assert!(matches_all("Recording of the demo", &terms("DEMO rec")));
+        assert!(!matches_all("Recording of the demo", &terms("rec release")));
+        // Prefixes only, not substrings in the middle of a word.
+        assert!(!matches_all("Recording", &terms("cord")));
+    }
+
+    #[test]
+    fn exact_phrase_in_the_name_scores_double() {
+        let session = indexed("Quarterly demo review", 0);
+        let (phrase_score, _) = score_session(&session, &terms("quarterly demo"), "quarterly demo");
+        let (words_score, _) = score_session(&session, &terms("demo quarterly"), "demo quarterly");
+        assert_eq!(phrase_score, NAME_WEIGHT * 2.0);
+        assert_eq!(words_score, NAME_WEIGHT);
+    }
+
+    #[test]
+    fn scores_every_field_and_reports_positions() {
+        let mut session = indexed("Standup", 0);
+        session.tags = vec!["demo".into(), "internal".into()];
+        session.notes = "Demo for the team".into();
+        session.markers = vec![Marker {
+            time_ms: 4_000,
+            label: "Demo starts".into(),
+        }];
+        session.transcript = vec![TranscriptSegment {
+            start_ms: 9_000,
+            end_ms: 11_000,
+            text: "let me demo this".into(),
+        }];
+
+        let (score, matches) = score_session(&session, &terms("demo"), "demo");
+        assert_eq!(
+            score,
+            TAG_WEIGHT + MARKER_WEIGHT + NOTES_WEIGHT + TRANSCRIPT_WEIGHT
+        );
+        let fields: Vec<(&str, Option<u64>)> =
+            matches.iter().map(|m| (m.field, m.time_ms)).collect();
+        assert_eq!(
+            fields,
+            [
+                ("tag", None),
+                ("marker", Some(4_000)),
+                ("notes", None),
+                ("transcript", Some(9_000))
+            ]
+        );
+    }
+
+    #[test]
+    fn orders_by_score_then_newest_first() {
+        let root = temp_root("order");
+        write_session(&root, "Session_1", "Old demo", 1_000, 1);
+        write_session(&root, "Session_2", "New demo", 2_000, 1);
+        write_session(&root, "Session_3", "Demo day", 500, 1);
+        write_session(&root, "Session_4", "Unrelated", 3_000, 1);
+        let all = search(&root, "demo day", 10).unwrap();
+        let ties = search(&root, "demo", 10).unwrap();
+        let limited = search(&root, "demo", 1).unwrap();
+        let empty = search(&root, " ?! ", 10).unwrap();
+        let _ = fs::remove_dir_all(&root);
+
+        let folders = |r: &[SearchResult]| r.iter().map(|r| r.folder.clone()).collect::<Vec<_>>();
+        assert_eq!(folders(&all), ["Session_3"]);
+        assert_eq!(folders(&ties), ["Session_2", "Session_1", "Session_3"]);
+        assert_eq!(folders(&limited), ["Session_2"]);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn refreshes_stale_and_removed_sessions() {
+        let root = temp_root("refresh");
+        write_session(&root, "Session_1", "First take", 1_000, 1);
+        write_session(&root, "Session_2", "Second take", 2_000, 1);
+        fs::create_dir_all(root.join("Session_3")).unwrap();
+        let first = refresh_index(&root).unwrap();
+        let written = root.join(INDEX_FILE).exists();
+
+        write_session(&root, "Session_1", "Renamed take", 1_000, 2);
+        fs::remove_dir_all(root.join("Session_2")).unwrap();
+        let renamed = search(&root, "renamed", 10).unwrap();
+        let stale = search(&root, "first", 10).unwrap();
+        let stored = load_index(&root);
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(written);
+        assert_eq!(first.sessions.len(), 2);
+        assert_eq!(first.sessions["Session_1"].stamp.0, 1_000);
+        assert!(!first.sessions.contains_key("Session_3"));
+        assert_eq!(renamed.len(), 1);
+        assert!(stale.is_empty());
+        assert_eq!(stored.sessions.len(), 1);
+        assert_eq!(stored.sessions["Session_1"].name, "Renamed take");
+        assert_eq!(stored.sessions["Session_1"].stamp.0, 2_000);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::encoding::Container;
+use crate::media::{self, MediaInfo};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Split recordings are written as `screen_part001.mp4`, `screen_part002.mp4`…
+const PART_PREFIX: &str = "screen_part";
+/// Every container a session video can be in.
+const EXTENSIONS: [&str; 2] = ["mp4", "mkv"];
+// Shortest part we'll cut, so a tiny size limit can't flood the folder.
+const MIN_SEGMENT_SECONDS: u64 = 10;
+
+/// Seconds per part for the requested split, or `None` for a single file.
+/// The segment muxer can only cut by time, so a size limit is turned into a
+/// duration from the estimated bitrate; parts come out roughly that size.
+pub fn segment_seconds(
+    minutes: Option<u32>,
+    size_mb: Option<u32>,
+    bytes_per_second: u64,
+) -> Option<u64> {
+    let by_time = minutes.filter(|m| *m > 0).map(|m| m as u64 * 60);
+    let by_size = size_mb
+        .filter(|mb| *mb > 0)
+        .map(|mb| mb as u64 * 1024 * 1024 / bytes_per_second.max(1));
+    let seconds = match (by_time, by_size) {
+        (Some(a), Some(b)) => a.min(b),
+        (a, b) => a.or(b)?,
+    };
+    Some(seconds.max(MIN_SEGMENT_SECONDS))
+}
+
+/// Output arguments that replace the single `screen.mp4` output. Parts
+/// start at a keyframe and each one plays on its own.
+pub fn output_args(session_dir: &Path, seconds: u64, container: Container) -> Vec<String> {
+    let pattern = session_dir.join(format!("{}%03d.{}", PART_PREFIX, container.extension()));
+    let mut args: Vec<String> = vec![
+        "-f".into(),
+        "segment".into(),
+        "-segment_time".into(),
+        seconds.to_string(),
+        "-segment_format".into(),
+        container.format().into(),
+    ];
+    if let Some(options) = container.mux_options() {
+        args.extend(["-segment_format_options".into(), options.into()]);
+    }
+    args.extend([
+        "-segment_start_number".into(),
+        "1".into(),
+        "-reset_timestamps".into(),
+        "1".into(),
+        pattern.to_string_lossy().to_string(),
+    ]);
+    args
+}
+
+/// The first part, which the recording is tracked by while it runs.
+pub fn first_part(session_dir: &Path, container: Container) -> PathBuf {
+    session_dir.join(format!("{}001.{}", PART_PREFIX, container.extension()))
+}
+
+fn part_number(file_name: &str) -> Option<u32> {
+    let (stem, extension) = file_name.rsplit_once('.')?;
+    if !EXTENSIONS.contains(&extension) {
+        return None;
+    }
+    stem.strip_prefix(PART_PREFIX)?.parse().ok()
+}
+
+/// File names of the parts in a session folder, in recording order.
+pub fn parts(session_dir: &Path) -> Vec<String> {
+    let Ok(dir) = fs::read_dir(session_dir) else {
+        return Vec::new();
+    };
+    let mut parts: Vec<(u32, String)> = dir
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            Some((part_number(&name)?, name))
+        })
+        .collect();
+    parts.sort();
+    parts.into_iter().map(|(_, name)| name).collect()
+}
+
+/// The video of a session recorded as one file: `screen.mp4` or `screen.mkv`.
+pub fn single_file(session_dir: &Path) -> Option<PathBuf> {
+    EXTENSIONS
+        .iter()
+        .map(|ext| session_dir.join(format!("screen.{}", ext)))
+        .find(|path| path.exists())
+}
+
+/// The file a session is played, previewed and probed by: its single file,
+/// or the first part of a split recording.
+pub fn video_path(session_dir: &Path) -> Option<PathBuf> {
+    if let Some(single) = single_file(session_dir) {
+        return Some(single);
+    }
+    parts(session_dir)
+        .first()
+        .map(|first| session_dir.join(first))
+}
+
+/// Combined size of the parts in bytes.
+pub fn total_size(session_dir: &Path, parts: &[String]) -> u64 {
+    parts
+        .iter()
+        .filter_map(|part| fs::metadata(session_dir.join(part)).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Probes every part, returning the combined duration in milliseconds and
+/// the stream info of the first part.
+pub fn probe(session_dir: &Path, parts: &[String]) -> Result<(u64, MediaInfo), String> {
+    let mut duration_ms = 0;
+    let mut first = None;
+    for part in parts {
+        let info = media::probe(&session_dir.join(part))?;
+        duration_ms += info.duration_ms;
+        first.get_or_insert(info);
+    }
+    first
+        .map(|info| (duration_ms, info))
+        .ok_or_else(|| "Recording has no parts".to_string())
+}
@@ -0,0 +1,500 @@
+use crate::disk;
+use crate::encoding::{Container, VideoEncoder};
+use crate::hotkeys::{HotkeyBindings, HotkeyMap};
+use crate::replay;
+use crate::retention::RetentionPolicy;
+use crate::scaling::ScaleMode;
+use crate::trash;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const SETTINGS_FILE: &str = "settings.json";
+/// Bump when a setting is renamed or changes meaning, and teach `migrate`
+/// how to bring older files up to date.
+pub const SETTINGS_VERSION: u32 = 2;
+// Slider drags send many updates; they are written to disk once they settle.
+const SAVE_DELAY: Duration = Duration::from_millis(1500);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum CaptureMode {
+    #[default]
+    Fullscreen,
+    Window,
+    Region,
+}
+
+impl CaptureMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CaptureMode::Fullscreen => "fullscreen",
+            CaptureMode::Window => "window",
+            CaptureMode::Region => "region",
+        }
+    }
+}
+
+/// Screen corner (or bottom edge) the keystroke overlay sits in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeystrokePosition {
+    #[default]
+    BottomLeft,
+    BottomRight,
+    TopLeft,
+    TopRight,
+    BottomCenter,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum WebcamShape {
+    #[default]
+    Square,
+    Portrait,
+    Landscape,
+}
+
+/// Everything the settings panel configures. Missing fields take their
+/// defaults, so older files and partial documents always load.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub mic_enabled: bool,
+    pub mic_device: String,
+    pub system_audio_enabled: bool,
+    pub webcam_enabled: bool,
+    pub capture_mode: CaptureMode,
+    pub show_clicks: bool,
+    pub show_keystrokes: bool,
+    pub keystroke_position: KeystrokePosition,
+    /// `#rrggbb` color of left-click ripples.
+    pub click_color_left: String,
+    pub click_color_right: String,
+    pub mic_volume: f32,
+    pub system_audio_volume: f32,
+    /// Empty for the default `Videos/Reframe`.
+    pub save_path: String,
+    /// Days before trashed recordings are deleted for good; 0 keeps them.
+    pub trash_retention_days: u64,
+    pub retention: RetentionPolicy,
+    /// Recordings stop when free space on the save drive falls below this.
+    pub low_disk_threshold_mb: u64,
+    /// Split recordings into parts every this many minutes.
+    pub segment_minutes: Option<u32>,
+    /// Split recordings into parts of roughly this size.
+    pub segment_size_mb: Option<u32>,
+    /// How much the replay buffer keeps, in seconds.
+    pub replay_buffer_seconds: u32,
+    pub webcam_shape: WebcamShape,
+    pub webcam_zoom: f32,
+    pub webcam_roundness: u32,
+    pub webcam_width: u32,
+    pub webcam_height: u32,
+    pub webcam_device: String,
+    /// A preset label such as "1080p FHD (1920x1080)"; labels without a
+    /// size record at the source resolution.
+    pub resolution: String,
+    /// How the capture is fitted into the resolution; `None` picks by
+    /// capture mode.
+    pub scale_mode: Option<ScaleMode>,
+    pub fps: u32,
+    pub encoder: VideoEncoder,
+    pub container: Container,
+    pub hotkeys: HotkeyBindings,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            mic_enabled: true,
+            mic_device: "Default".into(),
+            system_audio_enabled: false,
+            webcam_enabled: false,
+            capture_mode: CaptureMode::default(),
+            show_clicks: true,
+            show_keystrokes: true,
+            keystroke_position: KeystrokePosition::default(),
+            click_color_left: "#a3d95d".into(),
+            click_color_right: "#f97316".into(),
+            mic_volume: 1.0,
+            system_audio_volume: 1.0,
+            save_path: String::new(),
+            trash_retention_days: trash::DEFAULT_RETENTION_DAYS,
+            retention: RetentionPolicy::default(),
+            low_disk_threshold_mb: disk::DEFAULT_LOW_DISK_MB,
+            segment_minutes: None,
+            segment_size_mb: None,
+            replay_buffer_seconds: replay::DEFAULT_BUFFER_SECONDS,
+            webcam_shape: WebcamShape::default(),
+            webcam_zoom: 1.0,
+            // Half of it becomes the border radius in percent.
+            webcam_roundness: 20,
+            webcam_width: 300,
+            webcam_height: 300,
+            webcam_device: "Default".into(),
+            resolution: "1080p FHD (1920x1080)".into(),
+            scale_mode: None,
+            fps: 60,
+            encoder: VideoEncoder::default(),
+            container: Container::default(),
+            hotkeys: HotkeyBindings::default(),
+        }
+    }
+}
+
+/// Sent with the new settings after every change, so all windows follow.
+#[derive(Clone, Serialize, Deserialize, Type, tauri_specta::Event)]
+pub struct SettingsChanged(pub AppSettings);
+
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+impl AppSettings {
+    /// Output size picked by the resolution preset, if it names one.
+    pub fn resolution_size(&self) -> Option<(u32, u32)> {
+        let caps = Regex::new(r"(\d+)x(\d+)")
+            .unwrap()
+            .captures(&self.resolution)?;
+        Some((caps[1].parse().ok()?, caps[2].parse().ok()?))
+    }
+
+    /// Rejects values the panel never offers, so a bad update can't reach
+    /// FFmpeg or the overlays.
+    pub fn validate(&self) -> Result<(), String> {
+        for volume in [self.mic_volume, self.system_audio_volume] {
+            if !(0.0..=10.0).contains(&volume) {
+                return Err("Volume must be between 0 and 10".into());
+            }
+        }
+        for color in [&self.click_color_left, &self.click_color_right] {
+            if !is_hex_color(color) {
+                return Err(format!("Invalid click color: {}", color));
+            }
+        }
+        if !(1..=240).contains(&self.fps) {
+            return Err("Frame rate must be between 1 and 240".into());
+        }
+        if let Some((w, h)) = self.resolution_size() {
+            if !(16..=7680).contains(&w) || !(16..=4320).contains(&h) {
+                return Err(format!("Unsupported resolution {}x{}", w, h));
+            }
+        }
+        if self.segment_minutes == Some(0) || self.segment_size_mb == Some(0) {
+            return Err("Split limits must be above zero".into());
+        }
+        if !(1..=replay::MAX_BUFFER_SECONDS).contains(&self.replay_buffer_seconds) {
+            return Err(format!(
+                "Replay buffer must be between 1 and {} seconds",
+                replay::MAX_BUFFER_SECONDS
+            ));
+        }
+        if self.low_disk_threshold_mb > disk::MAX_LOW_DISK_MB {
+            return Err("Low disk threshold is too large".into());
+        }
+        if self.trash_retention_days > trash::MAX_RETENTION_DAYS {
+            return Err(format!(
+                "Trash retention must be at most {} days",
+                trash::MAX_RETENTION_DAYS
+            ));
+        }
+        if self.retention.max_total_gb.is_some_and(|gb| gb < 0.0) {
+            return Err("Library size limit can't be negative".into());
+        }
+        if !(100..=800).contains(&self.webcam_width) || !(100..=800).contains(&self.webcam_height) {
+            return Err("Webcam size must be between 100 and 800".into());
+        }
+        if !(1.0..=3.0).contains(&self.webcam_zoom) {
+            return Err("Webcam zoom must be between 1 and 3".into());
+        }
+        if self.webcam_roundness > 100 {
+            return Err("Webcam roundness must be between 0 and 100".into());
+        }
+        HotkeyMap::compile(self.hotkeys.clone()).map(|_| ())
+    }
+
+    /// A copy with `changes` (camelCase keys, as in the frontend) applied
+    /// and validated. Nested objects such as `hotkeys` are replaced whole.
+    pub fn with_changes(&self, changes: &Map<String, Value>) -> Result<AppSettings, String> {
+        let Value::Object(mut merged) = serde_json::to_value(self).map_err(|e| e.to_string())?
+        else {
+            unreachable!("settings serialize to an object");
+        };
+        for (key, value) in changes {
+            if !merged.contains_key(key) {
+                return Err(format!("Unknown setting: {}", key));
+            }
+            merged.insert(key.clone(), value.clone());
+        }
+        let settings: AppSettings = serde_json::from_value(Value::Object(merged))
+            .map_err(|e| format!("Invalid settings: {}", e))?;
+        settings.validate()?;
+        Ok(settings)
+    }
+}
+
+/// Brings a settings file from any earlier version to `SETTINGS_VERSION`.
+fn migrate(version: u32, mut settings: Map<String, Value>) -> Map<String, Value> {
+    if version < 1 {
+        // Version 0 was written by the dashboard through the store plugin.
+        // Its container choice was a display label.
+        if let Some(format) = settings.remove("videoFormat") {
+            let mkv = format.as_str().is_some_and(|f| f.contains("MKV"));
+            settings
+                .entry("container")
+                .or_insert_with(|| Value::from(if mkv { "mkv" } else { "mp4" }));
+        }
+        // The dashboard replaced this never-shown default on every load.
+        if settings.get("clickColorRight").and_then(Value::as_str) == Some("#f2a93b") {
+            settings.insert("clickColorRight".into(), "#f97316".into());
+        }
+    }
+    if version < 2 {
+        // The old Ctrl+Shift defaults clash with browser and editor
+        // shortcuts and are now reserved; move them to the new defaults.
+        let defaults = serde_json::to_value(HotkeyBindings::default()).unwrap_or_default();
+        if let Some(Value::Object(hotkeys)) = settings.get_mut("hotkeys") {
+            for (action, old) in [
+                ("startRecording", "Ctrl+Shift+R"),
+                ("stopRecording", "Ctrl+Shift+S"),
+                ("togglePause", "Ctrl+Shift+P"),
+                ("addMarker", "Ctrl+Shift+M"),
+                ("toggleWebcam", "Ctrl+Shift+W"),
+            ] {
+                if hotkeys.get(action).and_then(Value::as_str) == Some(old) {
+                    hotkeys.insert(action.into(), defaults[action].clone());
+                }
+            }
+        }
+    }
+    settings
+}
+
+// Applies stored values one by one so a single bad value only resets that
+// setting instead of the whole file.
+fn from_stored(stored: &Map<String, Value>) -> AppSettings {
+    let mut settings = AppSettings::default();
+    for (key, value) in stored {
+        let change = Map::from_iter([(key.clone(), value.clone())]);
+        match settings.with_changes(&change) {
+            Ok(updated) => settings = updated,
+            Err(e) => println!("Ignoring stored setting {}: {}", key, e),
+        }
+    }
+    settings
+}
+
+/// On-disk layout of `settings.json`.
+#[derive(Serialize)]
+struct SettingsFile<'a> {
+    version: u32,
+    settings: &'a AppSettings,
+}
+
+/// App settings persisted to the app data dir. The backend owns them: every
+/// window reads them with `get_settings` and follows `settings-changed`.
+pub struct SettingsStore {
+    settings: Mutex<AppSettings>,
+    store_path: PathBuf,
+    /// Set while a save is pending, so bursts of updates are written once.
+    save_scheduled: AtomicBool,
+}
+
+impl SettingsStore {
+    pub fn load(data_dir: &Path) -> Self {
+        let store_path = data_dir.join(SETTINGS_FILE);
+        let document: Map<String, Value> = match fs::read_to_string(&store_path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                println!("Ignoring unreadable {}: {}", SETTINGS_FILE, e);
+                Map::new()
+            }),
+            Err(_) => Map::new(),
+        };
+        // Files without a version predate the backend owning settings.
+        let version = document.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+        let stored = match document.get("settings") {
+            Some(Value::Object(settings)) => settings.clone(),
+            _ => Map::new(),
+        };
+        if version > SETTINGS_VERSION {
+            println!(
+                "{} is version {}, newer than supported ({}); reading what we can",
+                SETTINGS_FILE, version, SETTINGS_VERSION
+            );
+        }
+        let settings = from_stored(&migrate(version, stored));
+        let store = SettingsStore {
+            settings: Mutex::new(settings),
+            store_path,
+            save_scheduled: AtomicBool::new(false),
+        };
+        if version < SETTINGS_VERSION && store.store_path.exists() {
+            if let Err(e) = store.save() {
+                println!("{}", e);
+            }
+        }
+        store
+    }
+
+    pub fn get(&self) -> AppSettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    /// Applies `changes`, returning the previous and the new settings.
+    pub fn update(
+        &self,
+        changes: &Map<String, Value>,
+    ) -> Result<(AppSettings, AppSettings), String> {
+        let mut settings = self.settings.lock().unwrap();
+        let updated = settings.with_changes(changes)?;
+        let previous = std::mem::replace(&mut *settings, updated.clone());
+        Ok((previous, updated))
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let settings = self.get();
+        let document = SettingsFile {
+            version: SETTINGS_VERSION,
+            settings: &settings,
+        };
+        if let Some(dir) = self.store_path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(&document).map_err(|e| e.to_string())?;
+        let temp_path = self.store_path.with_extension("json.tmp");
+        fs::write(&temp_path, json).map_err(|e| format!("Failed to save settings: {}", e))?;
+        fs::rename(&temp_path, &self.store_path)
+            .map_err(|e| format!("Failed to save settings: {}", e))
+    }
+
+    /// Writes the settings to disk shortly, unless a save is already pending.
+    pub fn schedule_save(&self, app: &AppHandle) {
+        if self.save_scheduled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let app = app.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(SAVE_DELAY);
+            app.state::<SettingsStore>().flush();
+        });
+    }
+
+    /// Writes a pending save now, e.g. before the app exits.
+    pub fn flush(&self) {
+        if !self.save_scheduled.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        if let Err(e) = self.save() {
+            println!("{}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("not an object"),
+        }
+    }
+
+    #[test]
+    fn migrates_dashboard_settings() {
+        let stored = object(json!({
+            "videoFormat": "MKV (Matroska)",
+            "clickColorRight": "#f2a93b",
+            "fps": 30,
+            "hotkeys": { "startRecording": "Ctrl+Shift+R", "stopRecording": "F9" },
+        }));
+        let settings = from_stored(&migrate(0, stored));
+        assert_eq!(settings.container, Container::Mkv);
+        assert_eq!(settings.click_color_right, "#f97316");
+        assert_eq!(settings.fps, 30);
+        // Old defaults move to the new ones; custom bindings stay.
+        assert_eq!(settings.hotkeys.start_recording, "Ctrl+Alt+R");
+        assert_eq!(settings.hotkeys.stop_recording, "F9");
+    }
+
+    #[test]
+    fn migrates_only_the_steps_a_file_needs() {
+        let stored = object(json!({
+            "clickColorRight": "#f2a93b",
+            "hotkeys": { "togglePause": "Ctrl+Shift+P", "addMarker": "Ctrl+Shift+M" },
+        }));
+        let migrated = migrate(1, stored.clone());
+        assert_eq!(migrated["clickColorRight"], "#f2a93b");
+        assert_eq!(migrated["hotkeys"]["togglePause"], "Ctrl+Alt+P");
+        assert_eq!(migrated["hotkeys"]["addMarker"], "Ctrl+Alt+M");
+        assert_eq!(migrate(SETTINGS_VERSION, stored.clone()), stored);
+    }
+
+    #[test]
+    fn load_upgrades_the_file_and_round_trips() {
+        let dir = std::env::temp_dir().join(format!("reframe-settings-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SETTINGS_FILE);
+        fs::write(
+            &path,
+            json!({ "settings": { "videoFormat": "MP4", "fps": 24, "micVolume": 99 } }).to_string(),
+        )
+        .unwrap();
+
+        let first = SettingsStore::load(&dir).get();
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let second = SettingsStore::load(&dir).get();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(saved["version"], SETTINGS_VERSION);
+        assert_eq!(first.fps, 24);
+        assert_eq!(first.container, Container::Mp4);
+        // The out-of-range volume falls back on its own.
+        assert_eq!(first.mic_volume, 1.0);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn validate_accepts_the_defaults() {
+        assert_eq!(AppSettings::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_values() {
+        let cases: [fn(&mut AppSettings); 10] = [
+            |s| s.mic_volume = -1.0,
+            |s| s.click_color_left = "red".into(),
+            |s| s.fps = 0,
+            |s| s.resolution = "Tiny (8x8)".into(),
+            |s| s.segment_minutes = Some(0),
+            |s| s.replay_buffer_seconds = 0,
+            |s| s.trash_retention_days = u64::MAX,
+            |s| s.low_disk_threshold_mb = u64::MAX,
+            |s| s.webcam_zoom = 5.0,
+            |s| s.hotkeys.start_recording = "Ctrl+C".into(),
+        ];
+        for (i, change) in cases.into_iter().enumerate() {
+            let mut settings = AppSettings::default();
+            change(&mut settings);
+            assert!(settings.validate().is_err(), "case {} was accepted", i);
+        }
+    }
+
+    #[test]
+    fn with_changes_rejects_unknown_keys() {
+        let changes = object(json!({ "nope": true }));
+        assert!(AppSettings::default().with_changes(&changes).is_err());
+    }
+}
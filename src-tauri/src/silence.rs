@@ -0,0 +1,281 @@
+use crate::edit::{KeepRange, Segment};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const DEFAULT_THRESHOLD_DB: f64 = -35.0;
+pub const DEFAULT_MIN_DURATION_MS: u64 = 1_000;
+
+/// Silence kept around speech so cuts don't clip the first/last syllable.
+const EDGE_PADDING_MS: u64 = 150;
+
+/// A stretch of the recording where every audio track stayed below the
+/// threshold, in milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SilentRange {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// Result of a `silencedetect` pass, cached in the session manifest together
+/// with the parameters it was run with.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceAnalysis {
+    pub threshold_db: f64,
+    pub min_duration_ms: u64,
+    pub ranges: Vec<SilentRange>,
+    pub total_silent_ms: u64,
+    pub analyzed_at: u64,
+}
+
+impl SilenceAnalysis {
+    pub fn matches(&self, threshold_db: f64, min_duration_ms: u64) -> bool {
+        (self.threshold_db - threshold_db).abs() < 0.01 && self.min_duration_ms == min_duration_ms
+    }
+}
+
+// silencedetect logs "silence_start: 12.34" and
+// "silence_end: 15.6 | silence_duration: 3.26" on stderr. A trailing start
+// without an end means the file finished while silent.
+fn parse_silencedetect(log: &str, duration_ms: u64) -> Vec<SilentRange> {
+    fn value_after(line: &str, key: &str) -> Option<u64> {
+        let rest = &line[line.find(key)? + key.len()..];
+        let number = rest.split_whitespace().next()?;
+        let secs = number.parse::<f64>().ok()?;
+        Some((secs.max(0.0) * 1000.0).round() as u64)
+    }
+
+    let mut ranges = Vec::new();
+    let mut open: Option<u64> = None;
+    for line in log.lines() {
+        if let Some(start) = value_after(line, "silence_start:") {
+            open = Some(start);
+        } else if let Some(end) = value_after(line, "silence_end:") {
+            if let Some(start) = open.take() {
+                if end > start {
+                    ranges.push(SilentRange {
+                        start_ms: start,
+                        end_ms: end,
+                    });
+                }
+            }
+        }
+    }
+    if let Some(start) = open {
+        if duration_ms > start {
+            ranges.push(SilentRange {
+                start_ms: start,
+                end_ms: duration_ms,
+            });
+        }
+    }
+    ranges
+}
+
+// A moment only counts as silent when it is silent on every track, e.g. the
+// mic is quiet *and* no system audio is playing.
+fn intersect(a: &[SilentRange], b: &[SilentRange]) -> Vec<SilentRange> {
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let start = a[i].start_ms.max(b[j].start_ms);
+        let end = a[i].end_ms.min(b[j].end_ms);
+        if end > start {
+            out.push(SilentRange {
+                start_ms: start,
+                end_ms: end,
+            });
+        }
+        if a[i].end_ms < b[j].end_ms {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    out
+}
+
+// FFmpeg's stats line carries "time=00:01:02.50"; N/A before the first frame.
+fn parse_stats_time(line: &str) -> Option<u64> {
+    let rest = &line[line.find("time=")? + "time=".len()..];
+    let clock = rest.split_whitespace().next()?;
+    let mut secs = 0.0;
+    for part in clock.split(':') {
+        secs = secs * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some((secs.max(0.0) * 1000.0).round() as u64)
+}
+
+fn detect_track(
+    video: &Path,
+    track: usize,
+    threshold_db: f64,
+    min_duration_ms: u64,
+    duration_ms: u64,
+    cancel: &AtomicBool,
+    on_progress: &mut dyn FnMut(f64),
+) -> Result<Vec<SilentRange>, String> {
+    let mut child = crate::background_command("ffmpeg")
+        .arg("-hide_banner")
+        .arg("-i")
+        .arg(video)
+        .args([
+            "-map".to_string(),
+            format!("0:a:{}", track),
+            "-af".to_string(),
+            format!(
+                "silencedetect=noise={:.1}dB:d={:.3}",
+                threshold_db,
+                min_duration_ms as f64 / 1000.0
+            ),
+            "-f".to_string(),
+            "null".to_string(),
+            "-".to_string(),
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    // Stats lines end in '\r' so they overwrite each other on a terminal;
+    // split on both to see them as they arrive.
+    let mut log = String::new();
+    let stderr = child.stderr.take().unwrap();
+    for chunk in BufReader::new(stderr).split(b'\r').map_while(Result::ok) {
+        if cancel.load(Ordering::SeqCst) {
+            let _ = child.kill();
+            break;
+        }
+        let chunk = String::from_utf8_lossy(&chunk);
+        for line in chunk.lines() {
+            match parse_stats_time(line) {
+                Some(ms) if duration_ms > 0 => {
+                    on_progress((ms as f64 / duration_ms as f64).min(1.0));
+                }
+                Some(_) => {}
+                None => {
+                    log.push_str(line);
+                    log.push('\n');
+                }
+            }
+        }
+    }
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if cancel.load(Ordering::SeqCst) {
+        return Err(crate::CANCELLED.to_string());
+    }
+    if !status.success() {
+        return Err(format!(
+            "Silence detection failed: {}",
+            log.lines().last().unwrap_or("")
+        ));
+    }
+    Ok(parse_silencedetect(&log, duration_ms))
+}
+
+/// Runs `silencedetect` over each of the recording's audio tracks and keeps
+/// the stretches that are silent on all of them. Progress is split evenly
+/// between the tracks.
+pub fn analyze(
+    video: &Path,
+    audio_tracks: usize,
+    threshold_db: f64,
+    min_duration_ms: u64,
+    duration_ms: u64,
+    cancel: &AtomicBool,
+    on_progress: &mut dyn FnMut(f64),
+) -> Result<SilenceAnalysis, String> {
+    if audio_tracks == 0 {
+        return Err("Recording has no audio to analyze".to_string());
+    }
+    let mut ranges: Option<Vec<SilentRange>> = None;
+    for track in 0..audio_tracks {
+        let mut track_progress =
+            |fraction: f64| on_progress((track as f64 + fraction) / audio_tracks as f64);
+        let found = detect_track(
+            video,
+            track,
+            threshold_db,
+            min_duration_ms,
+            duration_ms,
+            cancel,
+            &mut track_progress,
+        )?;
+        ranges = Some(match ranges {
+            Some(previous) => intersect(&previous, &found),
+            None => found,
+        });
+    }
+    let mut ranges = ranges.unwrap_or_default();
+    // The intersection can leave slivers shorter than the requested minimum.
+    ranges.retain(|r| r.end_ms - r.start_ms >= min_duration_ms);
+
+    Ok(SilenceAnalysis {
+        threshold_db,
+        min_duration_ms,
+        total_silent_ms: ranges.iter().map(|r| r.end_ms - r.start_ms).sum(),
+        ranges,
+        analyzed_at: chrono::Local::now().timestamp_millis() as u64,
+    })
+}
+
+/// Splits the recording into alternating spoken and silent segments. Silent
+/// ones play at `silent_speed`, or are dropped when it is `None`.
+pub fn plan_segments(
+    silences: &[SilentRange],
+    duration_ms: u64,
+    silent_speed: Option<f64>,
+) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut cursor = 0;
+    for range in silences {
+        let start = (range.start_ms + EDGE_PADDING_MS).min(duration_ms);
+        let end = range
+            .end_ms
+            .saturating_sub(EDGE_PADDING_MS)
+            .min(duration_ms);
+        if end <= start || start < cursor {
+            continue;
+        }
+        if start > cursor {
+            segments.push(Segment {
+                start_ms: cursor,
+                end_ms: start,
+                speed: 1.0,
+            });
+        }
+        if let Some(speed) = silent_speed {
+            segments.push(Segment {
+                start_ms: start,
+                end_ms: end,
+                speed,
+            });
+        }
+        cursor = end;
+    }
+    if duration_ms > cursor {
+        segments.push(Segment {
+            start_ms: cursor,
+            end_ms: duration_ms,
+            speed: 1.0,
+        });
+    }
+    segments
+}
+
+/// The parts of the source that end up in the output, for the edit record.
+pub fn kept_ranges(segments: &[Segment]) -> Vec<KeepRange> {
+    segments
+        .iter()
+        .map(|s| KeepRange {
+            start_ms: s.start_ms,
+            end_ms: s.end_ms,
+        })
+        .collect()
+}
@@ -0,0 +1,148 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const POSTER_FILE: &str = "thumbnail.jpg";
+pub const SPRITE_FILE: &str = "preview.jpg";
+
+/// Number of evenly spaced frames laid out left to right in the sprite.
+pub const SPRITE_FRAMES: u32 = 10;
+const SPRITE_FRAME_WIDTH: u32 = 160;
+const POSTER_WIDTH: u32 = 480;
+
+pub struct Previews {
+    pub poster: Option<PathBuf>,
+    pub sprite: Option<PathBuf>,
+}
+
+fn generate_poster(
+    video: &Path,
+    target: &Path,
+    duration_ms: u64,
+    cancel: &AtomicBool,
+) -> Result<(), String> {
+    // A frame a little into the recording avoids the countdown/blank start.
+    let at = (duration_ms / 10).min(5_000) as f64 / 1000.0;
+    let args = vec![
+        "-y".into(),
+        "-ss".into(),
+        format!("{:.3}", at),
+        "-i".into(),
+        video.to_string_lossy().to_string(),
+        "-frames:v".into(),
+        "1".into(),
+        "-vf".into(),
+        format!("scale={}:-2", POSTER_WIDTH),
+        "-q:v".into(),
+        "3".into(),
+        target.to_string_lossy().to_string(),
+    ];
+    crate::run_ffmpeg_with_progress(args, target, 0, cancel, &mut |_| {})
+}
+
+// Seeks each input separately instead of decoding the whole file through an
+// `fps` filter, so long recordings take as long as short ones.
+fn generate_sprite(
+    video: &Path,
+    target: &Path,
+    duration_ms: u64,
+    cancel: &AtomicBool,
+) -> Result<(), String> {
+    let video = video.to_string_lossy().to_string();
+    let step = duration_ms as f64 / 1000.0 / SPRITE_FRAMES as f64;
+
+    let mut args: Vec<String> = vec!["-y".into()];
+    let mut filter = String::new();
+    for i in 0..SPRITE_FRAMES {
+        let at = step * (i as f64 + 0.5);
+        args.extend([
+            "-ss".into(),
+            format!("{:.3}", at),
+            "-i".into(),
+            video.clone(),
+        ]);
+        filter.push_str(&format!(
+            "[{i}:v]trim=end_frame=1,scale={w}:-2,setsar=1[f{i}];",
+            i = i,
+            w = SPRITE_FRAME_WIDTH
+        ));
+    }
+    for i in 0..SPRITE_FRAMES {
+        filter.push_str(&format!("[f{}]", i));
+    }
+    filter.push_str(&format!("hstack=inputs={}[out]", SPRITE_FRAMES));
+
+    args.extend([
+        "-filter_complex".into(),
+        filter,
+        "-map".into(),
+        "[out]".into(),
+        "-frames:v".into(),
+        "1".into(),
+        "-q:v".into(),
+        "5".into(),
+        target.to_string_lossy().to_string(),
+    ]);
+    crate::run_ffmpeg_with_progress(args, target, 0, cancel, &mut |_| {})
+}
+
+/// Reports the previews already present next to `video` without generating
+/// anything.
+pub fn existing_previews(video: &Path) -> Previews {
+    let Some(session_dir) = video.parent() else {
+        return Previews {
+            poster: None,
+            sprite: None,
+        };
+    };
+    let poster = session_dir.join(POSTER_FILE);
+    let sprite = session_dir.join(SPRITE_FILE);
+    Previews {
+        poster: poster.exists().then_some(poster),
+        sprite: sprite.exists().then_some(sprite),
+    }
+}
+
+/// Generates whichever of the poster and sprite sheet is missing from the
+/// session folder and reports the paths that exist afterwards. Stops early,
+/// leaving no partial image behind, once `cancel` is set.
+pub fn ensure_previews(
+    video: &Path,
+    duration_ms: u64,
+    cancel: &AtomicBool,
+    on_progress: &mut dyn FnMut(f64),
+) -> Previews {
+    let Some(session_dir) = video.parent() else {
+        return Previews {
+            poster: None,
+            sprite: None,
+        };
+    };
+    let poster = session_dir.join(POSTER_FILE);
+    let sprite = session_dir.join(SPRITE_FILE);
+
+    if !poster.exists() {
+        if let Err(e) = generate_poster(video, &poster, duration_ms, cancel) {
+            println!(
+                "Failed to generate thumbnail for {}: {}",
+                video.display(),
+                e
+            );
+        }
+    }
+    on_progress(0.5);
+    // Without a duration there is nothing to space the frames over.
+    if !sprite.exists() && duration_ms > 0 && !cancel.load(Ordering::SeqCst) {
+        if let Err(e) = generate_sprite(video, &sprite, duration_ms, cancel) {
+            println!(
+                "Failed to generate preview strip for {}: {}",
+                video.display(),
+                e
+            );
+        }
+    }
+
+    Previews {
+        poster: poster.exists().then_some(poster),
+        sprite: sprite.exists().then_some(sprite),
+    }
+}
@@ -0,0 +1,360 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+pub const TRANSCRIPT_FILE: &str = "transcript.json";
+pub const SRT_FILE: &str = "transcript.srt";
+pub const VTT_FILE: &str = "transcript.vtt";
+/// Folder under the app data dir where ggml Whisper models are looked up.
+pub const MODELS_DIR: &str = "models";
+pub const DEFAULT_MODEL: &str = "ggml-base.bin";
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Transcript {
+    pub model: String,
+    /// ISO 639-1 code, or "auto" when Whisper detected it.
+    pub language: String,
+    pub created_at: u64,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+/// Resolves a model picked in the UI, either a file name or a full path, to
+/// a file inside `models_dir`. Anything else is rejected, so the webview
+/// can't point Whisper at arbitrary files.
+pub fn model_path(models_dir: &Path, model: &str) -> Result<PathBuf, String> {
+    let requested = models_dir.join(model);
+    let not_found = || format!("Model not found: {}", requested.display());
+    let dir = fs::canonicalize(models_dir).map_err(|_| not_found())?;
+    let resolved = fs::canonicalize(&requested).map_err(|_| not_found())?;
+    if !resolved.starts_with(&dir) || !resolved.is_file() {
+        return Err(format!("Models must be in the {} folder", MODELS_DIR));
+    }
+    Ok(resolved)
+}
+
+// Whisper segments can carry line breaks; a blank line would end the cue.
+fn cue_text(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn timestamp(ms: u64, separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        (ms / 60_000) % 60,
+        (ms / 1000) % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+pub fn to_srt(transcript: &Transcript) -> String {
+    let mut out = String::new();
+    for (i, segment) in transcript.segments.iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            timestamp(segment.start_ms, ','),
+            timestamp(segment.end_ms, ','),
+            cue_text(&segment.text)
+        ));
+    }
+    out
+}
+
+pub fn to_vtt(transcript: &Transcript) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for segment in &transcript.segments {
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            timestamp(segment.start_ms, '.'),
+            timestamp(segment.end_ms, '.'),
+            cue_text(&segment.text)
+        ));
+    }
+    out
+}
+
+/// Writes `transcript.json`, `.srt` and `.vtt` into the session folder.
+pub fn write_outputs(session_dir: &Path, transcript: &Transcript) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(transcript).map_err(|e| e.to_string())?;
+    for (file, contents) in [
+        (TRANSCRIPT_FILE, json),
+        (SRT_FILE, to_srt(transcript)),
+        (VTT_FILE, to_vtt(transcript)),
+    ] {
+        fs::write(session_dir.join(file), contents)
+            .map_err(|e| format!("Failed to write {}: {}", file, e))?;
+    }
+    Ok(())
+}
+
+pub fn load(session_dir: &Path) -> Option<Transcript> {
+    let text = fs::read_to_string(session_dir.join(TRANSCRIPT_FILE)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// Adds the SRT as a soft subtitle track (`mov_text` in MP4, SRT in MKV),
+/// replacing any subtitle track from an earlier transcription. Rewrites the
+/// file in place.
+pub fn mux_subtitles(video_path: &Path, srt_path: &Path, language: &str) -> Result<(), String> {
+    let is_mkv = video_path.extension().is_some_and(|ext| ext == "mkv");
+    let (ext, subtitle_codec) = if is_mkv {
+        ("mkv", "srt")
+    } else {
+        ("mp4", "mov_text")
+    };
+    let temp_path = video_path.with_extension(format!("subs.{}", ext));
+    let mut args: Vec<String> = vec![
+        "-y".into(),
+        "-i".into(),
+        video_path.to_string_lossy().to_string(),
+        "-i".into(),
+        srt_path.to_string_lossy().to_string(),
+        "-map".into(),
+        "0".into(),
+        "-map".into(),
+        "-0:s?".into(),
+        "-map".into(),
+        "1".into(),
+        "-c".into(),
+        "copy".into(),
+        "-c:s".into(),
+        subtitle_codec.into(),
+    ];
+    args.extend([
+        "-metadata:s:s:0".into(),
+        format!("title=Transcript ({})", language),
+    ]);
+    args.push(temp_path.to_string_lossy().to_string());
+
+    if let Err(e) = crate::run_ffmpeg(args) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    fs::rename(&temp_path, video_path).map_err(|e| format!("Failed to replace video: {}", e))
+}
+
+// Whisper wants 16 kHz mono f32 samples; FFmpeg mixes and resamples for us.
+#[cfg(feature = "transcription")]
+fn decode_audio(video_path: &Path) -> Result<Vec<f32>, String> {
+    let output = crate::background_command("ffmpeg")
+        .args(["-v", "error", "-i"])
+        .arg(video_path)
+        .args(["-vn", "-ac", "1", "-ar", "16000", "-f", "f32le", "-"])
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to decode audio: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output
+        .stdout
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+/// Runs Whisper over the recording's audio. `language` of `None` lets the
+/// model detect it.
+#[cfg(feature = "transcription")]
+pub fn transcribe(
+    video_path: &Path,
+    model_path: &Path,
+    language: Option<&str>,
+    cancel: &Arc<AtomicBool>,
+    on_progress: &mut (dyn FnMut(f64) + Send),
+) -> Result<Transcript, String> {
+    use std::sync::atomic::Ordering;
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+    if !model_path.exists() {
+        return Err(format!("Model not found: {}", model_path.display()));
+    }
+    let samples = decode_audio(video_path)?;
+    let context = WhisperContext::new_with_params(
+        &model_path.to_string_lossy(),
+        WhisperContextParameters::default(),
+    )
+    .map_err(|e| format!("Failed to load model: {}", e))?;
+    let mut state = context.create_state().map_err(|e| e.to_string())?;
+
+    let (progress_tx, progress_rx) = mpsc::channel();
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_language(Some(language.unwrap_or("auto")));
+    params.set_n_threads(
+        std::thread::available_parallelism()
+            .map(|n| n.get().min(8) as i32)
+            .unwrap_or(4),
+    );
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_special(false);
+    params.set_print_timestamps(false);
+    params.set_progress_callback_safe(move |percent: i32| {
+        let _ = progress_tx.send(percent);
+    });
+    let abort = cancel.clone();
+    params.set_abort_callback_safe(move || abort.load(Ordering::SeqCst));
+
+    // Whisper's callbacks must be 'static, so progress goes through a channel
+    // that a scoped thread drains while `full` blocks this one.
+    let done = AtomicBool::new(false);
+    let result = std::thread::scope(|scope| {
+        let done = &done;
+        scope.spawn(move || {
+            while !done.load(Ordering::SeqCst) {
+                if let Ok(percent) = progress_rx.recv_timeout(Duration::from_millis(250)) {
+                    on_progress(percent as f64 / 100.0);
+                }
+            }
+        });
+        let result = state.full(params, &samples);
+        done.store(true, Ordering::SeqCst);
+        result
+    });
+    if cancel.load(Ordering::SeqCst) {
+        return Err("Transcription cancelled".into());
+    }
+    result.map_err(|e| format!("Transcription failed: {}", e))?;
+
+    let count = state.full_n_segments().map_err(|e| e.to_string())?;
+    let mut segments = Vec::with_capacity(count as usize);
+    for i in 0..count {
+        let text = state
+            .full_get_segment_text_lossy(i)
+            .map_err(|e| e.to_string())?;
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        // Segment times are in centiseconds.
+        let t0 = state.full_get_segment_t0(i).map_err(|e| e.to_string())?;
+        let t1 = state.full_get_segment_t1(i).map_err(|e| e.to_string())?;
+        segments.push(TranscriptSegment {
+            start_ms: t0.max(0) as u64 * 10,
+            end_ms: t1.max(0) as u64 * 10,
+            text: text.to_string(),
+        });
+    }
+
+    Ok(Transcript {
+        model: model_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        language: language.unwrap_or("auto").to_string(),
+        created_at: chrono::Local::now().timestamp_millis() as u64,
+        segments,
+    })
+}
+
+#[cfg(not(feature = "transcription"))]
+pub fn transcribe(
+    _video_path: &Path,
+    _model_path: &Path,
+    _language: Option<&str>,
+    _cancel: &Arc<AtomicBool>,
+    _on_progress: &mut (dyn FnMut(f64) + Send),
+) -> Result<Transcript, String> {
+    Err("This build was compiled without transcription support".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcript(segments: &[(u64, u64, &str)]) -> Transcript {
+        Transcript {
+            model: DEFAULT_MODEL.into(),
+            language: "en".into(),
+            created_at: 0,
+            segments: segments
+                .iter()
+                .map(|&(start_ms, end_ms, text)| TranscriptSegment {
+                    start_ms,
+                    end_ms,
+                    text: text.into(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn formats_timestamps_for_each_format() {
+        assert_eq!(timestamp(0, ','), "00:00:00,000");
+        assert_eq!(timestamp(61_005, ','), "00:01:01,005");
+        assert_eq!(timestamp(3_723_456, '.'), "01:02:03.456");
+        assert_eq!(timestamp(100 * 3_600_000, '.'), "100:00:00.000");
+    }
+
+    #[test]
+    fn writes_numbered_srt_and_vtt_cues() {
+        let t = transcript(&[(0, 1_500, "Hello"), (1_500, 62_000, "World")]);
+        assert_eq!(
+            to_srt(&t),
+            "1\n00:00:00,000 --> 00:00:01,500\nHello\n\n\
+             2\n00:00:01,500 --> 00:01:02,000\nWorld\n\n"
+        );
+        assert_eq!(
+            to_vtt(&t),
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:01.500\nHello\n\n\
+             00:00:01.500 --> 00:01:02.000\nWorld\n\n"
+        );
+    }
+
+    #[test]
+    fn blank_lines_never_split_a_cue() {
+        let t = transcript(&[(0, 1_000, " First line\n\n  \nSecond line \n")]);
+        assert_eq!(
+            to_srt(&t),
+            "1\n00:00:00,000 --> 00:00:01,000\nFirst line\nSecond line\n\n"
+        );
+        assert!(to_vtt(&t).contains("\nFirst line\nSecond line\n\n"));
+    }
+
+    #[test]
+    fn only_loads_models_from_the_models_folder() {
+        let root = std::env::temp_dir().join(format!("reframe-models-{}", std::process::id()));
+        let models = root.join(MODELS_DIR);
+        fs::create_dir_all(&models).unwrap();
+        fs::write(models.join(DEFAULT_MODEL), "").unwrap();
+        fs::write(root.join("secret.bin"), "").unwrap();
+
+        let by_name = model_path(&models, DEFAULT_MODEL);
+        let by_path = model_path(&models, &models.join(DEFAULT_MODEL).to_string_lossy());
+        let outside = model_path(&models, &root.join("secret.bin").to_string_lossy());
+        let escaped = model_path(&models, "../secret.bin");
+        let missing = model_path(&models, "ggml-large.bin");
+        let folder = model_path(&models, ".");
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(by_name.is_ok_and(|p| p.ends_with(DEFAULT_MODEL)));
+        assert!(by_path.is_ok());
+        assert!(outside.is_err());
+        assert!(escaped.is_err());
+        assert!(missing.unwrap_err().starts_with("Model not found"));
+        assert!(folder.is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{guard, manifest};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Lives in the save folder; the library and search skip it because its
+/// name doesn't start with `Session_`.
+pub const TRASH_DIR: &str = ".trash";
+/// Written into each trashed session folder.
+const TRASH_INFO_FILE: &str = "trashed.json";
+pub const DEFAULT_RETENTION_DAYS: u64 = 30;
+pub const MAX_RETENTION_DAYS: u64 = 3650;
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrashInfo {
+    /// Folder name the session had in the save root.
+    folder: String,
+    deleted_at: u64,
+}
+
+#[derive(Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedRecording {
+    /// Folder name inside the trash; identifies the entry for restoring.
+    pub id: String,
+    pub folder: String,
+    pub name: String,
+    pub deleted_at: u64,
+    pub size_bytes: u64,
+}
+
+fn now_millis() -> u64 {
+    chrono::Local::now().timestamp_millis() as u64
+}
+
+fn read_info(entry_dir: &Path) -> Option<TrashInfo> {
+    let text = fs::read_to_string(entry_dir.join(TRASH_INFO_FILE)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// `dir/folder`, or `dir/folder_2` and up when that is taken, so deleting a
+/// session of the same name twice never overwrites the first.
+pub fn free_name(dir: &Path, folder: &str) -> PathBuf {
+    let mut candidate = dir.join(folder);
+    let mut n = 2;
+    while candidate.exists() {
+        candidate = dir.join(format!("{}_{}", folder, n));
+        n += 1;
+    }
+    candidate
+}
+
+/// Moves a session folder into the trash of the save root it lives in and
+/// returns the id to restore it with.
+pub fn move_to_trash(session_dir: &Path) -> Result<String, String> {
+    let folder = session_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or("Invalid recording path")?;
+    let root = session_dir.parent().ok_or("Invalid recording path")?;
+    let trash_dir = root.join(TRASH_DIR);
+    fs::create_dir_all(&trash_dir).map_err(|e| format!("Failed to create trash: {}", e))?;
+
+    let target = free_name(&trash_dir, &folder);
+    fs::rename(session_dir, &target)
+        .map_err(|e| format!("Failed to move recording to trash: {}", e))?;
+    let info = TrashInfo {
+        folder,
+        deleted_at: now_millis(),
+    };
+    let json = serde_json::to_string_pretty(&info).map_err(|e| e.to_string())?;
+    fs::write(target.join(TRASH_INFO_FILE), json)
+        .map_err(|e| format!("Failed to record deletion: {}", e))?;
+    Ok(target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default())
+}
+
+/// Trashed recordings under `root`, most recently deleted first.
+pub fn list(root: &Path) -> Vec<TrashedRecording> {
+    let Ok(dir) = fs::read_dir(root.join(TRASH_DIR)) else {
+        return Vec::new();
+    };
+    let mut items: Vec<TrashedRecording> = dir
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let info = read_info(&path)?;
+            let name = manifest::load(&path)
+                .ok()
+                .flatten()
+                .map(|m| m.name)
+                .unwrap_or_else(|| info.folder.clone());
+            Some(TrashedRecording {
+                id: entry.file_name().to_string_lossy().to_string(),
+                folder: info.folder,
+                name,
+                deleted_at: info.deleted_at,
+                size_bytes: crate::dir_size(&path),
+            })
+        })
+        .collect();
+    items.sort_by_key(|i| std::cmp::Reverse(i.deleted_at));
+    items
+}
+
+/// Moves a trashed recording back to its original folder name.
+pub fn restore(root: &Path, id: &str) -> Result<PathBuf, String> {
+    if !guard::is_session_folder(id) {
+        return Err("Recording is not in the trash".into());
+    }
+    let entry_dir = guard::within(root, &root.join(TRASH_DIR).join(id))
+        .map_err(|_| "Recording is not in the trash".to_string())?;
+    if !entry_dir.is_dir() {
+        return Err("Recording is not in the trash".into());
+    }
+    let info = read_info(&entry_dir).ok_or("Recording is not in the trash")?;
+    // The info file is just JSON on disk; never let it name a path elsewhere.
+    if !guard::is_session_folder(&info.folder) {
+        return Err(format!("Invalid recording folder: {}", info.folder));
+    }
+    let target = root.join(&info.folder);
+    if target.exists() {
+        return Err(format!("A recording named {} already exists", info.folder));
+    }
+    fs::rename(&entry_dir, &target).map_err(|e| format!("Failed to restore recording: {}", e))?;
+    let _ = fs::remove_file(target.join(TRASH_INFO_FILE));
+    Ok(target)
+}
+
+/// Permanently deletes everything in the trash.
+pub fn empty(root: &Path) -> Result<(), String> {
+    let trash_dir = root.join(TRASH_DIR);
+    if trash_dir.exists() {
+        fs::remove_dir_all(&trash_dir).map_err(|e| format!("Failed to empty trash: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Deletes trashed recordings older than `retention_days`. Zero keeps them
+/// until the trash is emptied. Returns how many were removed.
+pub fn purge_expired(root: &Path, retention_days: u64) -> usize {
+    if retention_days == 0 {
+        return 0;
+    }
+    let cutoff = now_millis().saturating_sub(retention_days.saturating_mul(DAY_MS));
+    let mut purged = 0;
+    for item in list(root).into_iter().filter(|i| i.deleted_at < cutoff) {
+        match fs::remove_dir_all(root.join(TRASH_DIR).join(&item.id)) {
+            Ok(()) => purged += 1,
+            Err(e) => println!("Failed to purge {} from trash: {}", item.id, e),
+        }
+    }
+    purged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("reframe-trash-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("Session_1")).unwrap();
+        fs::write(root.join("Session_1").join("screen.mp4"), b"video").unwrap();
+        root
+    }
+
+    #[test]
+    fn restores_to_the_original_folder() {
+        let root = library("restore");
+        let id = move_to_trash(&root.join("Session_1")).unwrap();
+        assert!(!root.join("Session_1").exists());
+        let restored = restore(&root, &id);
+        let exists = root.join("Session_1").join("screen.mp4").exists();
+        let _ = fs::remove_dir_all(&root);
+        assert!(restored.is_ok() && exists);
+    }
+
+    #[test]
+    fn rejects_entries_that_name_a_folder_outside_the_root() {
+        let root = library("escape");
+        let id = move_to_trash(&root.join("Session_1")).unwrap();
+        let entry_dir = root.join(TRASH_DIR).join(&id);
+        let mut results = Vec::new();
+        for folder in [
+            "../escaped",
+            "Session_1/../../escaped",
+            "/tmp/escaped",
+            "escaped",
+        ] {
+            let info = TrashInfo {
+                folder: folder.into(),
+                deleted_at: 0,
+            };
+            fs::write(
+                entry_dir.join(TRASH_INFO_FILE),
+                serde_json::to_string(&info).unwrap(),
+            )
+            .unwrap();
+            results.push(restore(&root, &id).is_err());
+        }
+        results.push(restore(&root, "../Session_1").is_err());
+        results.push(restore(&root, TRASH_DIR).is_err());
+        let still_trashed = entry_dir.exists();
+        let _ = fs::remove_dir_all(&root);
+        assert!(results.iter().all(|rejected| *rejected), "{:?}", results);
+        assert!(still_trashed);
+    }
+}
@@ -0,0 +1,120 @@
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_specta::Event as _;
+
+/// Quiet period before a burst of changes is reported.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+/// Upper bound on how long a steady stream of writes can hold events back.
+const MAX_DELAY: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Serialize, Deserialize, Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryChanged {
+    pub folder: String,
+    pub path: String,
+    /// False when the session folder was deleted or renamed away.
+    pub exists: bool,
+}
+
+/// Watches a save root for as long as it is alive; dropping it stops the
+/// watcher and its debounce thread.
+pub struct LibraryWatcher {
+    root: PathBuf,
+    _watcher: RecommendedWatcher,
+}
+
+impl LibraryWatcher {
+    pub fn start(app: &AppHandle, root: &Path) -> Result<Self, String> {
+        let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+        let mut watcher =
+            notify::recommended_watcher(tx).map_err(|e| format!("Failed to watch: {}", e))?;
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {}", root.display(), e))?;
+
+        let app = app.clone();
+        let thread_root = root.to_path_buf();
+        std::thread::spawn(move || {
+            let mut pending = HashSet::new();
+            let mut first_pending = Instant::now();
+            loop {
+                let timeout = if pending.is_empty() {
+                    Duration::MAX
+                } else {
+                    DEBOUNCE.min(MAX_DELAY.saturating_sub(first_pending.elapsed()))
+                };
+                match rx.recv_timeout(timeout) {
+                    Ok(Ok(event)) => {
+                        if matches!(event.kind, EventKind::Access(_)) {
+                            continue;
+                        }
+                        if pending.is_empty() {
+                            first_pending = Instant::now();
+                        }
+                        pending.extend(
+                            event
+                                .paths
+                                .iter()
+                                .filter_map(|p| session_folder(&thread_root, p)),
+                        );
+                    }
+                    Ok(Err(e)) => println!("Library watcher error: {}", e),
+                    Err(RecvTimeoutError::Timeout) => {
+                        flush(&app, &thread_root, pending.drain());
+                    }
+                    // The watcher was dropped.
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+
+        println!("Watching library at {}", root.display());
+        Ok(Self {
+            root: root.to_path_buf(),
+            _watcher: watcher,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+// The session folder a changed path belongs to. Changes to files directly in
+// the root, like the search index, aren't library changes.
+fn session_folder(root: &Path, path: &Path) -> Option<String> {
+    let folder = path.strip_prefix(root).ok()?.components().next()?;
+    let folder = folder.as_os_str().to_string_lossy();
+    folder.starts_with("Session_").then(|| folder.to_string())
+}
+
+fn flush(app: &AppHandle, root: &Path, folders: impl Iterator<Item = String>) {
+    // The session being recorded changes constantly; it is reported by
+    // `recording-stopped` once finished.
+    let recording = app
+        .state::<crate::AppState>()
+        .recording
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|r| r.output_path.parent().map(Path::to_path_buf));
+
+    for folder in folders {
+        let path = root.join(&folder);
+        if recording.as_deref() == Some(path.as_path()) {
+            continue;
+        }
+        let _ = LibraryChanged {
+            exists: path.is_dir(),
+            path: path.to_string_lossy().to_string(),
+            folder,
+        }
+        .emit(app);
+    }
+}
@@ -1,6 +1,4 @@
 import React, { useState, useEffect } from 'react';
-import { invoke } from '@tauri-apps/api/core';
-import { emit } from '@tauri-apps/api/event';
 import { getCurrentWindow, LogicalSize } from '@tauri-apps/api/window';
 import {
   Mic,
@@ -26,6 +24,14 @@ import {
   CheckSquare,
   Square as SquareIcon,
   Trash,
+  Download,
+  Search,
+  X,
+  Star,
+  Tag,
+  Hourglass,
+  RotateCcw,
+  History,
 } from 'lucide-react';
 import logo from './assets/logo.png';
 import InteractionLayer from './components/InteractionLayer';
@@ -33,19 +39,46 @@ import AudioMeter from './components/AudioMeter';
 import Toggle from './components/Toggle';
 import { useSettings } from './hooks/useSettings';
 import FloatingBar from './components/FloatingBar';
+import RecordingThumbnail from './components/RecordingThumbnail';
 import { SettingsPanel } from './components/SettingsPanel';
 import { AboutPanel } from './components/AboutPanel';
 import { CustomSelect } from './components/CustomSelect';
-
-export interface FileRecord {
-  id: number;
-  name: string;
-  duration: string;
-  size: string;
-  folder: string;
-  files: string[];
-  fullPath?: string;
-}
+import { commands, events } from './bindings';
+import type {
+  DeviceList,
+  DiskInfo,
+  ExportPreset,
+  FileRecord,
+  HotkeyAction,
+  Job,
+  RecordingOptions,
+  RecordingProfile,
+  SearchResult,
+  TagCount,
+  TrashedRecording,
+} from './bindings';
+
+const PAGE_SIZE = 60;
+
+export const formatDuration = (ms: number) => {
+  const secs = Math.floor(ms / 1000);
+  const hrs = Math.floor(secs / 3600);
+  const mins = Math.floor((secs % 3600) / 60);
+  const s = secs % 60;
+  return `${hrs > 0 ? `${hrs}:` : ''}${mins.toString().padStart(2, '0')}:${s.toString().padStart(2, '0')}`;
+};
+
+export const formatSize = (bytes: number) => `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+
+const EXPORT_PRESETS: { id: ExportPreset; label: string; description: string }[] = [
+  { id: 'mp4-1080p', label: 'MP4 1080p', description: 'H.264, best for sharing' },
+  { id: 'mp4-720p', label: 'MP4 720p', description: 'Smaller H.264 file' },
+  { id: 'webm', label: 'WebM', description: 'VP9 + Opus for the web' },
+  { id: 'vertical', label: 'Vertical 9:16', description: 'Center crop for shorts' },
+  { id: 'gif', label: 'GIF', description: 'Optimized palette, no audio' },
+  { id: 'mp3', label: 'MP3', description: 'Audio only' },
+  { id: 'opus', label: 'Opus', description: 'Audio only, smaller' },
+];
 
 interface AppProps {
   mode?: 'controls' | 'overlay';
@@ -59,36 +92,55 @@ export default function App({ mode = 'controls' }: AppProps) {
   const [countdown, setCountdown] = useState<number | null>(null);
   const [showStopConfirmation, setShowStopConfirmation] = useState(false);
   const [pendingRecordingName, setPendingRecordingName] = useState('');
-  const [diskInfo, setDiskInfo] = useState<{ free: number, total: number, label: string } | null>(null);
-  const [sortMode, setSortMode] = useState<'newest' | 'name' | 'size'>('newest');
+  const [diskInfo, setDiskInfo] = useState<DiskInfo | null>(null);
+  const [sortMode, setSortMode] = useState<'newest' | 'name' | 'duration' | 'size'>('newest');
   const [showSortMenu, setShowSortMenu] = useState(false);
   const [renamingFile, setRenamingFile] = useState<FileRecord | null>(null);
   const [deletingFile, setDeletingFile] = useState<FileRecord | null>(null);
+  const [trashUndo, setTrashUndo] = useState<string[] | null>(null);
+  const [showTrash, setShowTrash] = useState(false);
+  const [trashItems, setTrashItems] = useState<TrashedRecording[]>([]);
+  const [exportingFile, setExportingFile] = useState<FileRecord | null>(null);
+  const [exportJob, setExportJob] = useState<Job | null>(null);
+  const [editingDetails, setEditingDetails] = useState<FileRecord | null>(null);
+  const [detailsTags, setDetailsTags] = useState('');
+  const [detailsNotes, setDetailsNotes] = useState('');
+  const [tagFilter, setTagFilter] = useState<string | null>(null);
+  const [libraryTags, setLibraryTags] = useState<TagCount[]>([]);
+  const [searchQuery, setSearchQuery] = useState('');
+  const [searchResults, setSearchResults] = useState<SearchResult[] | null>(null);
   const [newName, setNewName] = useState('');
-  const [activeMenuId, setActiveMenuId] = useState<number | null>(null);
+  const [activeMenuId, setActiveMenuId] = useState<string | null>(null);
   const [viewMode, setViewMode] = useState<'list' | 'grid'>('list');
-  const [selectedIds, setSelectedIds] = useState<Set<number>>(new Set());
+  const [selectedIds, setSelectedIds] = useState<Set<string>>(new Set());
+  const [markerCount, setMarkerCount] = useState(0);
+  const [replayRunning, setReplayRunning] = useState(false);
+  const [profiles, setProfiles] = useState<RecordingProfile[]>([]);
+  const [selectedProfileId, setSelectedProfileId] = useState('');
+  const [savingReplay, setSavingReplay] = useState(false);
 
   const finalDurationRef = React.useRef(0);
   const sessionPathRef = React.useRef<string>('');
+  // Handlers for recordings driven from the backend (global hotkeys); assigned on each render
+  const backendEventsRef = React.useRef<{
+    onStarted: (sessionPath: string) => void;
+    onStopped: (outputPath: string, reason: string) => void;
+    onHotkey: (action: HotkeyAction) => void;
+    onJob: (job: Job) => void;
+    onLibraryChanged: () => void;
+  } | null>(null);
 
-  const { settings, updateSettings, loaded } = useSettings();
+  const { settings, updateSettings } = useSettings();
 
   // Unified Webcam window lifecycle management
   useEffect(() => {
-    if (!loaded || mode !== 'controls') return;
+    if (mode !== 'controls') return;
 
     const syncWebcam = async () => {
       try {
         console.log('[WebcamSync] Desired state:', settings.webcamEnabled);
-        await invoke('toggle_webcam', { show: settings.webcamEnabled });
-
-        if (settings.webcamEnabled) {
-          // Broadcast full settings to ensure the new webcam window is up to date
-          setTimeout(() => {
-            emit('settings-sync', settings).catch(console.error);
-          }, 400);
-        }
+        // The webcam window reads its settings from the backend when it opens
+        await commands.toggleWebcam(settings.webcamEnabled);
       } catch (e) {
         console.error('[WebcamSync] Failed:', e);
       }
@@ -97,51 +149,167 @@ export default function App({ mode = 'controls' }: AppProps) {
     // Small delay to ensure Tauri's window manager is ready (especially on boot)
     const timer = setTimeout(syncWebcam, 300);
     return () => clearTimeout(timer);
-  }, [settings.webcamEnabled, loaded, mode]);
+  }, [settings.webcamEnabled, mode]);
   const [recordings, setRecordings] = useState<FileRecord[]>([]);
-  const [availableDevices, setAvailableDevices] = useState<{ audio: string[], video: string[] }>({ audio: [], video: [] });
+  const [totalRecordings, setTotalRecordings] = useState(0);
+  const [availableDevices, setAvailableDevices] = useState<DeviceList>({ audio: [], video: [] });
 
   useEffect(() => {
     if (mode === 'overlay') {
       import('@tauri-apps/api/window').then(({ getCurrentWindow }) => {
         getCurrentWindow().setIgnoreCursorEvents(true);
       });
-      invoke('start_global_listener').catch(e => console.error("Failed to start global listener:", e));
+      commands.startGlobalListener().catch(e => console.error("Failed to start global listener:", e));
       return;
     }
 
-    invoke<{ audio: string[], video: string[] }>('get_input_devices')
+    commands.getInputDevices()
       .then(devices => {
         setAvailableDevices(devices);
       })
       .catch(err => console.error("Failed to fetch devices:", err));
+  }, [mode]);
 
-    // Fetch disk info
+  // Space on the drive holding the save folder
+  useEffect(() => {
+    if (mode === 'overlay') return;
     const updateDiskInfo = () => {
-      invoke<any>('get_disk_info')
+      commands.getDiskInfo()
         .then(info => setDiskInfo(info))
         .catch(err => console.error("Failed to fetch disk info:", err));
     };
     updateDiskInfo();
     const diskInterval = setInterval(updateDiskInfo, 30000); // Update every 30s
     return () => clearInterval(diskInterval);
-  }, [mode]);
+  }, [mode, settings.savePath]);
 
-  const refreshRecordings = async () => {
+  const refreshRecordings = async (append = false) => {
     try {
-      const recs = await invoke<FileRecord[]>('list_recordings', { savePath: settings.savePath });
-      setRecordings(recs);
-      setSelectedIds(new Set()); // Reset selection on refresh
+      const page = await commands.listRecordings({
+        sort: sortMode === 'newest' ? 'date' : sortMode,
+        ascending: null,
+        from: null,
+        to: null,
+        minDurationMs: null,
+        tag: tagFilter,
+        format: null,
+        favoritesOnly: false,
+        folders: searchResults ? searchResults.map(r => r.folder) : null,
+        offset: append ? recordings.length : 0,
+        limit: PAGE_SIZE,
+      });
+      setTotalRecordings(page.total);
+      if (append) {
+        setRecordings(prev => [...prev, ...page.items]);
+      } else {
+        setRecordings(page.items);
+        setSelectedIds(new Set()); // Reset selection on refresh
+      }
+      setLibraryTags(await commands.listTags());
     } catch (e) {
       console.error("Failed to list recordings:", e);
     }
   };
 
   useEffect(() => {
-    if (loaded) {
-      refreshRecordings();
+    refreshRecordings();
+  }, [settings.savePath, tagFilter, sortMode, searchResults]);
+
+  const updateMetadata = (rec: FileRecord, changes: { tags?: string[], notes?: string, favorite?: boolean }) => {
+    return commands.updateRecordingMetadata(rec.folder, changes.tags ?? null, changes.notes ?? null, changes.favorite ?? null)
+      .then(() => refreshRecordings())
+      .catch(e => console.error("Failed to update recording:", e));
+  };
+
+  const moveToTrash = async (recs: FileRecord[]) => {
+    const ids = await Promise.all(recs.map(rec =>
+      commands.deleteRecording(rec.folder)
+        .catch(e => {
+          console.error("Failed to delete recording:", e);
+          return null;
+        })
+    ));
+    setTrashUndo(ids.filter((id): id is string => !!id));
+    refreshRecordings();
+  };
+
+  const refreshTrash = () =>
+    commands.listTrash()
+      .then(setTrashItems)
+      .catch(e => console.error("Failed to list trash:", e));
+
+  const restoreFromTrash = async (ids: string[]) => {
+    for (const id of ids) {
+      await commands.restoreRecording(id)
+        .catch(e => alert(`Failed to restore recording: ${e}`));
     }
-  }, [loaded, settings.savePath]);
+    refreshRecordings();
+    if (showTrash) refreshTrash();
+  };
+
+  // The undo offer goes away after a few seconds; the trash keeps the files
+  useEffect(() => {
+    if (!trashUndo) return;
+    const timer = setTimeout(() => setTrashUndo(null), 8000);
+    return () => clearTimeout(timer);
+  }, [trashUndo]);
+
+  // Debounced full-text search over names, tags, markers, notes and transcripts
+  useEffect(() => {
+    if (!searchQuery.trim()) {
+      setSearchResults(null);
+      return;
+    }
+    const timer = setTimeout(() => {
+      commands.searchRecordings(searchQuery, null)
+        .then(setSearchResults)
+        .catch(e => console.error("Search failed:", e));
+    }, 250);
+    return () => clearTimeout(timer);
+  }, [searchQuery, settings.savePath]);
+
+  // Follow the save folder so external changes show up in the library
+  useEffect(() => {
+    if (mode !== 'controls') return;
+    commands.watchLibrary()
+      .catch(e => console.error("Failed to watch library:", e));
+  }, [mode, settings.savePath]);
+
+  // Recordings started/stopped by global hotkeys never go through the dashboard buttons
+  useEffect(() => {
+    if (mode !== 'controls') return;
+    const unlisteners = [
+      events.recordingStarted.listen((event) => {
+        if (event.payload.trigger !== 'command') backendEventsRef.current?.onStarted(event.payload.sessionPath);
+      }),
+      events.recordingStopped.listen((event) => {
+        if (event.payload.reason !== 'command') backendEventsRef.current?.onStopped(event.payload.path, event.payload.reason);
+      }),
+      events.lowDiskWarning.listen((event) => {
+        alert(`Low disk space: about ${event.payload.minutesRemaining} minutes of recording fit on this drive before recording stops automatically.`);
+      }),
+      events.replayBufferChanged.listen((event) => {
+        setReplayRunning(event.payload.running);
+      }),
+      events.hotkeyAction.listen((event) => {
+        backendEventsRef.current?.onHotkey(event.payload);
+      }),
+      events.markerAdded.listen(() => {
+        setMarkerCount(count => count + 1);
+      }),
+      events.jobProgress.listen((event) => {
+        setExportJob(job => (job && job.id === event.payload.id ? event.payload : job));
+        backendEventsRef.current?.onJob(event.payload);
+      }),
+      // Sessions added, removed or changed outside the app
+      events.libraryChanged.listen(() => {
+        backendEventsRef.current?.onLibraryChanged();
+      }),
+    ];
+    return () => {
+      unlisteners.forEach(p => p.then(unlisten => unlisten()));
+    };
+  }, [mode]);
 
   if (mode === 'overlay') {
     return (
@@ -159,14 +327,6 @@ export default function App({ mode = 'controls' }: AppProps) {
     finalDurationRef.current = t;
   }, []);
 
-  const formatTime = (secs: number) => {
-    if (!secs) return "00:00";
-    const hrs = Math.floor(secs / 3600);
-    const mins = Math.floor((secs % 3600) / 60);
-    const s = secs % 60;
-    return `${hrs > 0 ? `${hrs}:` : ''}${mins.toString().padStart(2, '0')}:${s.toString().padStart(2, '0')}`;
-  };
-
   const playBeep = (frequency = 440, type: OscillatorType = 'sine', duration = 0.2) => {
     try {
       const ctx = new (window.AudioContext || (window as any).webkitAudioContext)();
@@ -207,49 +367,110 @@ export default function App({ mode = 'controls' }: AppProps) {
     }, 1200);
   };
 
-  const startRecordingActual = async () => {
+  // Options for the backend capture, shared by recordings and the replay buffer
+  const buildRecordingOptions = (): RecordingOptions => {
+    let actualMicDevice = settings.micDevice;
+    if (actualMicDevice === 'Default' && availableDevices.audio.length > 0) {
+      const realDevice = availableDevices.audio.find(d => d !== 'Default');
+      if (realDevice) actualMicDevice = realDevice;
+    }
+
+    // "Original" records at the source resolution
+    const resMatch = settings.resolution.match(/(\d+)x(\d+)/);
+
+    return {
+      name: pendingRecordingName.trim() || null,
+      micEnabled: settings.micEnabled,
+      micDevice: actualMicDevice,
+      systemAudioEnabled: settings.systemAudioEnabled,
+      captureMode: settings.captureMode,
+      windowTitle: '',
+      region: '',
+      micVolume: settings.micVolume,
+      systemAudioVolume: settings.systemAudioVolume,
+      framerate: settings.fps,
+      width: resMatch ? parseInt(resMatch[1]) : null,
+      height: resMatch ? parseInt(resMatch[2]) : null,
+      scaleMode: settings.scaleMode,
+      segmentMinutes: settings.segmentMinutes,
+      segmentSizeMb: settings.segmentSizeMb,
+      encoder: settings.encoder,
+      container: settings.container
+    };
+  };
+
+  useEffect(() => {
+    if (mode !== 'controls') return;
+    commands.getReplayStatus()
+      .then(status => setReplayRunning(status.running))
+      .catch(e => console.error('Failed to read replay buffer status:', e));
+  }, [mode]);
+
+  const toggleReplayBuffer = async () => {
     try {
-      let actualMicDevice = settings.micDevice;
-      if (actualMicDevice === 'Default' && availableDevices.audio.length > 0) {
-        const realDevice = availableDevices.audio.find(d => d !== 'Default');
-        if (realDevice) actualMicDevice = realDevice;
+      if (replayRunning) {
+        await commands.stopReplayBuffer();
+      } else {
+        await commands.startReplayBuffer(buildRecordingOptions(), settings.replayBufferSeconds);
       }
+    } catch (e) {
+      console.error('Failed to toggle replay buffer:', e);
+      alert('Replay buffer: ' + e);
+    }
+  };
 
-      const rustOptions = {
-        micEnabled: settings.micEnabled,
-        micDevice: actualMicDevice,
-        systemAudioEnabled: settings.systemAudioEnabled,
-        savePath: settings.savePath,
-        captureMode: settings.captureMode,
-        windowTitle: '',
-        region: '',
-        micVolume: settings.micVolume,
-        systemAudioVolume: settings.systemAudioVolume,
-        framerate: settings.fps,
-        width: null as number | null,
-        height: null as number | null
-      };
-
-      // Parse resolution
-      const resMatch = settings.resolution.match(/(\d+)x(\d+)/);
-      if (resMatch) {
-        // @ts-ignore
-        rustOptions.width = parseInt(resMatch[1]);
-        // @ts-ignore
-        rustOptions.height = parseInt(resMatch[2]);
-      }
+  const saveReplay = async () => {
+    setSavingReplay(true);
+    try {
+      await commands.saveReplay(null, pendingRecordingName.trim() || null);
+      setPendingRecordingName('');
+      refreshRecordings();
+    } catch (e) {
+      console.error('Failed to save replay:', e);
+      alert('Failed to save replay: ' + e);
+    } finally {
+      setSavingReplay(false);
+    }
+  };
+
+  const refreshProfiles = React.useCallback(() => {
+    commands.listProfiles()
+      .then(list => {
+        setProfiles(list);
+        setSelectedProfileId(id => (list.some(p => p.id === id) ? id : ''));
+      })
+      .catch(e => console.error('Failed to load profiles:', e));
+  }, []);
+
+  // Stable for the memoized settings panel, yet always reading current settings
+  const buildOptionsRef = React.useRef(buildRecordingOptions);
+  buildOptionsRef.current = buildRecordingOptions;
+  const currentRecordingOptions = React.useCallback(() => buildOptionsRef.current(), []);
+
+  useEffect(() => {
+    if (mode === 'controls') refreshProfiles();
+  }, [mode]);
 
-      const res = await invoke<string>('start_recording', { options: JSON.stringify(rustOptions) });
-      sessionPathRef.current = res;
-      setIsRecording(true);
-      await getCurrentWindow().setDecorations(false);
-      await getCurrentWindow().setSize(new LogicalSize(600, 140));
+  const startRecordingActual = async () => {
+    try {
+      const res = selectedProfileId
+        ? await commands.startRecordingWithProfile(selectedProfileId, pendingRecordingName.trim() || null)
+        : await commands.startRecording(buildRecordingOptions());
+      await enterRecordingLayout(res);
     } catch (e) {
       console.error("Failed to start recording:", e);
       alert("Failed to start recording: " + e);
     }
   };
 
+  const enterRecordingLayout = async (sessionPath: string) => {
+    sessionPathRef.current = sessionPath;
+    setMarkerCount(0);
+    setIsRecording(true);
+    await getCurrentWindow().setDecorations(false);
+    await getCurrentWindow().setSize(new LogicalSize(600, 140));
+  };
+
   const handleStopRecording = () => {
     setShowStopConfirmation(true);
   };
@@ -258,30 +479,25 @@ export default function App({ mode = 'controls' }: AppProps) {
     setShowStopConfirmation(false);
     setIsStopping(true); // Stop timer updates immediately
     try {
-      const result = await invoke<{ path: string, size: string }>('stop_recording');
-      const outputPath = result.path;
+      const result = await commands.stopRecording();
+      await finishRecording(result.path);
+    } catch (e) {
+      console.error("Failed to stop recording:", e);
+      setIsStopping(false); // Reset on error
+    }
+  };
 
+  const finishRecording = async (outputPath: string) => {
+    try {
+      setShowStopConfirmation(false);
       setIsRecording(false);
       setIsPaused(false);
       setIsStopping(false); // Reset stopping state
       await getCurrentWindow().setDecorations(true);
       await getCurrentWindow().setSize(new LogicalSize(900, 600));
 
-      const duration = finalDurationRef.current;
-      const formattedDuration = formatTime(duration);
-      const defaultName = `Recording ${new Date().toLocaleDateString()} ${new Date().toLocaleTimeString()}`;
-      const finalName = pendingRecordingName.trim() || defaultName;
-
-      // Save metadata to disk
-      const folderPath = outputPath.substring(0, outputPath.lastIndexOf('\\'));
-      await invoke('save_metadata', {
-        path: folderPath,
-        metadata: JSON.stringify({
-          name: finalName,
-          duration: formattedDuration,
-          timestamp: Date.now()
-        })
-      });
+      // The backend writes the session manifest (name, duration, markers) on stop
+      console.log("Recording saved:", outputPath);
 
       // Switch to library and refresh
       setActiveTab('library');
@@ -290,7 +506,7 @@ export default function App({ mode = 'controls' }: AppProps) {
       finalDurationRef.current = 0;
       setPendingRecordingName('');
     } catch (e) {
-      console.error("Failed to stop recording:", e);
+      console.error("Failed to finalize recording:", e);
       setIsStopping(false); // Reset on error
     }
   };
@@ -299,20 +515,33 @@ export default function App({ mode = 'controls' }: AppProps) {
     setShowStopConfirmation(false);
   };
 
-  /* --- WRAP APP CONTENT WITH ERROR BOUNDARY TO DEBUG CRASHES --- */
-  if (!loaded) {
-    return (
-      <div className="flex flex-col items-center justify-center w-full h-full bg-black text-white p-4">
-        <h2 className="text-xl font-bold animate-pulse">Loading Application...</h2>
-        <div className="text-xs text-zinc-500 mt-2 font-mono">Loading settings and dependencies</div>
-      </div>
-    );
-  }
-
   const toggleWebcam = async () => {
     updateSettings({ webcamEnabled: !settings.webcamEnabled }, true);
   };
 
+  backendEventsRef.current = {
+    onStarted: (sessionPath) => {
+      enterRecordingLayout(sessionPath).catch(e => console.error("Failed to enter recording layout:", e));
+    },
+    onStopped: (outputPath, reason) => {
+      setIsStopping(true);
+      finishRecording(outputPath).then(() => {
+        if (reason === 'low_disk') alert("Recording stopped because the drive is almost full. Everything up to that point was saved.");
+      });
+    },
+    onHotkey: (action) => {
+      if (action === 'togglePause' && isRecording) setIsPaused(!isPaused);
+      if (action === 'toggleWebcam') toggleWebcam();
+    },
+    onJob: (job) => {
+      // Thumbnails are generated in the background; show them once ready
+      if (job.kind.type === 'thumbnails' && job.status === 'done') refreshRecordings();
+    },
+    onLibraryChanged: () => {
+      if (!isRecording) refreshRecordings();
+    },
+  };
+
   return (
     <div className="w-full h-full bg-inherit font-sans text-zinc-100 relative overflow-hidden select-none">
 
@@ -340,6 +569,8 @@ export default function App({ mode = 'controls' }: AppProps) {
               initialSeconds={finalDurationRef.current}
               webcamEnabled={settings.webcamEnabled}
               toggleWebcam={toggleWebcam}
+              onAddMarker={() => commands.addMarker(null).catch(e => console.error("Failed to add marker:", e))}
+              markerCount={markerCount}
             />
           ) : (
             /* Stop Confirmation Dialog (replaces bar temporarily) */
@@ -566,7 +797,18 @@ export default function App({ mode = 'controls' }: AppProps) {
                   </div>
 
                   {/* Footer: Name Input & Start Button */}
-                  <div className="h-24 shrink-0 flex flex-col items-center justify-center gap-3 border-t border-white/5 pt-4">
+                  <div className="shrink-0 flex flex-col items-center justify-center gap-3 border-t border-white/5 pt-4">
+                    {profiles.length > 0 && (
+                      <CustomSelect
+                        className="w-full max-w-sm"
+                        options={[
+                          { value: '', label: 'Current settings' },
+                          ...profiles.map(p => ({ value: p.id, label: p.name })),
+                        ]}
+                        value={selectedProfileId}
+                        onChange={setSelectedProfileId}
+                      />
+                    )}
                     <div className="w-full max-w-sm relative">
                       <input
                         type="text"
@@ -590,6 +832,27 @@ export default function App({ mode = 'controls' }: AppProps) {
                         </>
                       )}
                     </button>
+                    <div className="w-full max-w-sm flex items-center gap-2">
+                      <button
+                        onClick={toggleReplayBuffer}
+                        disabled={countdown !== null}
+                        className={`flex-1 flex items-center justify-center gap-2 px-3 py-2 rounded-lg text-xs font-medium border transition-colors disabled:opacity-50 ${replayRunning ? 'bg-indigo-500/20 border-indigo-500/40 text-indigo-300' : 'bg-white/5 border-white/10 text-zinc-400 hover:text-zinc-200'}`}
+                        title={`Continuously keeps the last ${settings.replayBufferSeconds}s so it can be saved after the fact`}
+                      >
+                        <History size={14} />
+                        {replayRunning ? 'Stop Replay Buffer' : 'Start Replay Buffer'}
+                      </button>
+                      {replayRunning && (
+                        <button
+                          onClick={saveReplay}
+                          disabled={savingReplay}
+                          className="flex-1 flex items-center justify-center gap-2 px-3 py-2 rounded-lg text-xs font-medium bg-indigo-500 hover:bg-indigo-400 text-white transition-colors disabled:opacity-50"
+                        >
+                          <Download size={14} />
+                          {savingReplay ? 'Saving...' : 'Save Replay'}
+                        </button>
+                      )}
+                    </div>
                   </div>
                 </div>
               )}
@@ -603,25 +866,34 @@ export default function App({ mode = 'controls' }: AppProps) {
                   <div className="flex items-center justify-between mb-6">
                     <div className="flex items-center gap-4">
                       <h2 className="text-2xl font-bold">Recordings</h2>
+                      <div className="relative">
+                        <Search size={14} className="absolute left-2.5 top-1/2 -translate-y-1/2 text-zinc-500 pointer-events-none" />
+                        <input
+                          type="text"
+                          value={searchQuery}
+                          onChange={(e) => setSearchQuery(e.target.value)}
+                          onKeyDown={(e) => e.key === 'Escape' && setSearchQuery('')}
+                          placeholder="Search recordings..."
+                          className="w-56 bg-white/5 border border-white/5 focus:border-indigo-500 rounded-lg pl-8 pr-7 py-1.5 text-xs text-white placeholder:text-zinc-500 outline-none transition-all"
+                        />
+                        {searchQuery && (
+                          <button
+                            onClick={() => setSearchQuery('')}
+                            className="absolute right-2 top-1/2 -translate-y-1/2 text-zinc-500 hover:text-white"
+                          >
+                            <X size={12} />
+                          </button>
+                        )}
+                      </div>
                     </div>
 
                     <div className="flex gap-2 relative">
                       {selectedIds.size > 0 && (
                         <button
                           onClick={() => {
-                            const toDelete = recordings.filter(r => selectedIds.has(r.id as number));
-                            if (window.confirm(`Delete ${selectedIds.size} recordings forever?`)) {
-                              Promise.all(toDelete.map(r => {
-                                const path = r.fullPath;
-                                if (path) {
-                                  const folderPath = path.substring(0, path.lastIndexOf('\\'));
-                                  return invoke('delete_recording', { path: folderPath });
-                                }
-                                return Promise.resolve();
-                              })).then(() => {
-                                setSelectedIds(new Set());
-                                refreshRecordings();
-                              });
+                            const toDelete = recordings.filter(r => selectedIds.has(r.id));
+                            if (window.confirm(`Move ${selectedIds.size} recordings to the trash?`)) {
+                              moveToTrash(toDelete).then(() => setSelectedIds(new Set()));
                             }
                           }}
                           className="flex items-center gap-2 px-3 py-1.5 bg-red-500/10 border border-red-500/20 text-red-500 rounded-lg hover:bg-red-500 hover:text-white transition-all text-xs font-bold"
@@ -633,7 +905,7 @@ export default function App({ mode = 'controls' }: AppProps) {
                       <button
                         onClick={() => {
                           if (selectedIds.size === recordings.length && recordings.length > 0) setSelectedIds(new Set());
-                          else setSelectedIds(new Set(recordings.map(r => r.id as number)));
+                          else setSelectedIds(new Set(recordings.map(r => r.id)));
                         }}
                         className="p-2 hover:bg-white/5 rounded text-zinc-400 hover:text-white transition-colors"
                         title="Select All"
@@ -642,7 +914,18 @@ export default function App({ mode = 'controls' }: AppProps) {
                       </button>
 
                       <button
-                        onClick={() => invoke('open_folder', { path: settings.savePath })}
+                        onClick={() => {
+                          setShowTrash(true);
+                          refreshTrash();
+                        }}
+                        className="p-2 hover:bg-white/5 rounded text-zinc-400 hover:text-white transition-colors"
+                        title="Trash"
+                      >
+                        <Trash size={18} />
+                      </button>
+
+                      <button
+                        onClick={() => commands.openFolder(null)}
                         className="p-2 hover:bg-white/5 rounded text-zinc-400 hover:text-white transition-colors"
                         title="Open Folder"
                       >
@@ -661,6 +944,7 @@ export default function App({ mode = 'controls' }: AppProps) {
                             {[
                               { id: 'newest', label: 'Newest First', icon: Clock },
                               { id: 'name', label: 'By Name', icon: ArrowDownAZ },
+                              { id: 'duration', label: 'By Duration', icon: Hourglass },
                               { id: 'size', label: 'By Size', icon: HardDrive },
                             ].map((mode) => (
                               <button
@@ -700,25 +984,41 @@ export default function App({ mode = 'controls' }: AppProps) {
                     </div>
                   </div>
 
-                  {recordings.length === 0 ? (
+                  {libraryTags.length > 0 && (
+                    <div className="flex flex-wrap items-center gap-1.5 -mt-3 mb-4">
+                      <Tag size={12} className="text-zinc-500 mr-1" />
+                      {libraryTags.map(tag => (
+                        <button
+                          key={tag.name}
+                          onClick={() => setTagFilter(tagFilter === tag.name ? null : tag.name)}
+                          className={`px-2 py-0.5 rounded-full text-[10px] font-bold border transition-all ${tagFilter === tag.name ? 'bg-indigo-500 border-indigo-500 text-white' : 'bg-white/5 border-white/5 text-zinc-400 hover:text-white hover:border-white/20'}`}
+                        >
+                          {tag.name} <span className="opacity-60">{tag.count}</span>
+                        </button>
+                      ))}
+                    </div>
+                  )}
+
+                  {searchResults && recordings.length === 0 ? (
+                    <div className="flex-1 flex flex-col items-center justify-center text-zinc-500 gap-4 border-2 border-dashed border-white/5 rounded-xl">
+                      <Search size={48} className="opacity-20" />
+                      <p>No recordings match "{searchQuery}".</p>
+                    </div>
+                  ) : recordings.length === 0 ? (
                     <div className="flex-1 flex flex-col items-center justify-center text-zinc-500 gap-4 border-2 border-dashed border-white/5 rounded-xl">
                       <Film size={48} className="opacity-20" />
-                      <p>No recordings yet. Start creating!</p>
+                      <p>{tagFilter ? `No recordings tagged "${tagFilter}".` : 'No recordings yet. Start creating!'}</p>
                     </div>
                   ) : (
                     <div className={viewMode === 'list' ? "space-y-2 overflow-y-auto custom-scrollbar pr-2" : "grid grid-cols-2 lg:grid-cols-3 gap-3 overflow-y-auto custom-scrollbar pr-2"}>
                       {[...recordings]
                         .sort((a, b) => {
-                          if (sortMode === 'newest') return (b.id as number) - (a.id as number);
-                          if (sortMode === 'name') return a.name.localeCompare(b.name);
-                          if (sortMode === 'size') {
-                            const parseSize = (s: string) => parseFloat(s.split(' ')[0]) || 0;
-                            return parseSize(b.size) - parseSize(a.size);
-                          }
-                          return 0;
+                          // Search results keep their relevance order
+                          if (!searchResults) return 0;
+                          return searchResults.findIndex(r => r.folder === a.folder) - searchResults.findIndex(r => r.folder === b.folder);
                         })
                         .map((rec) => {
-                          const isSelected = selectedIds.has(rec.id as number);
+                          const isSelected = selectedIds.has(rec.id);
                           return (
                             <div
                               key={rec.id}
@@ -729,8 +1029,8 @@ export default function App({ mode = 'controls' }: AppProps) {
                                 onClick={(e) => {
                                   e.stopPropagation();
                                   const next = new Set(selectedIds);
-                                  if (isSelected) next.delete(rec.id as number);
-                                  else next.add(rec.id as number);
+                                  if (isSelected) next.delete(rec.id);
+                                  else next.add(rec.id);
                                   setSelectedIds(next);
                                 }}
                                 className={`absolute top-2 left-2 w-4 h-4 rounded border transition-all cursor-pointer flex items-center justify-center z-20 ${isSelected ? 'bg-indigo-500 border-indigo-500' : 'bg-black/60 border-white/20 opacity-0 group-hover:opacity-100'}`}
@@ -739,27 +1039,64 @@ export default function App({ mode = 'controls' }: AppProps) {
                               </div>
 
                               <div
-                                onClick={() => invoke('open_file', { path: rec.fullPath })}
+                                onClick={() => commands.openFile(rec.fullPath)}
                                 className={`${viewMode === 'list' ? 'w-10 h-10' : 'w-full aspect-video'} bg-black/60 rounded-lg flex items-center justify-center shrink-0 cursor-pointer group/play overflow-hidden relative`}
                               >
-                                <Play size={viewMode === 'list' ? 16 : 24} className="text-zinc-500 group-hover/play:text-indigo-400 group-hover/play:scale-110 transition-all fill-zinc-500 group-hover/play:fill-indigo-400/20 z-10" />
+                                <RecordingThumbnail
+                                  thumbnail={rec.thumbnail}
+                                  previewSprite={viewMode === 'grid' ? rec.previewSprite : null}
+                                  previewFrames={rec.previewFrames}
+                                />
+                                <Play size={viewMode === 'list' ? 16 : 24} className="text-zinc-500 group-hover/play:text-indigo-400 group-hover/play:scale-110 transition-all fill-zinc-500 group-hover/play:fill-indigo-400/20 z-10 pointer-events-none" />
                                 {viewMode === 'grid' && (
-                                  <div className="absolute inset-0 bg-gradient-to-t from-black/80 via-transparent to-transparent flex items-end p-2">
-                                    <div className="text-[10px] font-mono text-zinc-400">{rec.duration}</div>
+                                  <div className="absolute inset-0 bg-gradient-to-t from-black/80 via-transparent to-transparent flex items-end p-2 pointer-events-none">
+                                    <div className="text-[10px] font-mono text-zinc-400">{formatDuration(rec.durationMs)}</div>
                                   </div>
                                 )}
                               </div>
 
                               <div className="flex-1 min-w-0">
-                                <h4 className={`font-bold text-zinc-100 truncate ${viewMode === 'list' ? 'text-xs' : 'text-sm mb-0.5'}`}>{rec.name}</h4>
+                                <div className="flex items-center gap-1.5 min-w-0">
+                                  <button
+                                    onClick={(e) => {
+                                      e.stopPropagation();
+                                      updateMetadata(rec, { favorite: !rec.favorite });
+                                    }}
+                                    className={`shrink-0 transition-colors ${rec.favorite ? 'text-amber-400' : 'text-zinc-600 hover:text-zinc-300'}`}
+                                    title={rec.favorite ? 'Remove from favorites' : 'Add to favorites'}
+                                  >
+                                    <Star size={12} className={rec.favorite ? 'fill-amber-400' : ''} />
+                                  </button>
+                                  <h4 className={`font-bold text-zinc-100 truncate ${viewMode === 'list' ? 'text-xs' : 'text-sm mb-0.5'}`}>{rec.name}</h4>
+                                  {rec.tags.slice(0, 3).map(tag => (
+                                    <span key={tag} className="shrink-0 px-1.5 rounded-full bg-indigo-500/10 text-indigo-300 text-[9px] font-bold">{tag}</span>
+                                  ))}
+                                </div>
+                                {(() => {
+                                  const match = searchResults?.find(r => r.folder === rec.folder)?.matches.find(m => m.field !== 'name');
+                                  if (!match) return null;
+                                  return (
+                                    <div className="text-[10px] text-zinc-400 truncate">
+                                      {match.timeMs !== null && <span className="text-indigo-400 font-mono mr-1">{formatDuration(match.timeMs)}</span>}
+                                      <span className="text-zinc-500 mr-1">{match.field}:</span>
+                                      {match.text}
+                                    </div>
+                                  );
+                                })()}
                                 <div className={`flex items-center gap-2 text-zinc-500 font-medium ${viewMode === 'list' ? 'text-[9px]' : 'text-[10px]'}`}>
                                   <span className="truncate flex items-center gap-1"><Folder size={9} className="opacity-50" /> {rec.folder}</span>
                                   <span>•</span>
-                                  <span>{rec.size}</span>
+                                  <span>{formatSize(rec.sizeBytes)}</span>
+                                  {rec.height > 0 && (
+                                    <>
+                                      <span>•</span>
+                                      <span>{rec.height}p{Math.round(rec.fps)}</span>
+                                    </>
+                                  )}
                                   {viewMode === 'list' && (
                                     <>
                                       <span>•</span>
-                                      <span className="text-indigo-400 font-bold">{rec.duration}</span>
+                                      <span className="text-indigo-400 font-bold">{formatDuration(rec.durationMs)}</span>
                                     </>
                                   )}
                                 </div>
@@ -769,7 +1106,7 @@ export default function App({ mode = 'controls' }: AppProps) {
                                 <button
                                   onClick={(e) => {
                                     e.stopPropagation();
-                                    setActiveMenuId(activeMenuId === rec.id ? null : rec.id as number);
+                                    setActiveMenuId(activeMenuId === rec.id ? null : rec.id);
                                   }}
                                   className={`p-1 rounded-md transition-all ${activeMenuId === rec.id ? 'bg-indigo-500 text-white' : 'hover:bg-white/10 text-zinc-400'}`}
                                 >
@@ -803,6 +1140,41 @@ export default function App({ mode = 'controls' }: AppProps) {
                                           <span className="text-[10px] text-zinc-500">Update file label</span>
                                         </div>
                                       </button>
+                                      <button
+                                        onClick={(e) => {
+                                          e.stopPropagation();
+                                          setActiveMenuId(null);
+                                          setEditingDetails(rec);
+                                          setDetailsTags(rec.tags.join(', '));
+                                          setDetailsNotes(rec.notes);
+                                        }}
+                                        className="w-full flex items-center gap-3 px-3 py-2.5 text-sm text-zinc-300 hover:bg-white/10 hover:text-white rounded-xl transition-all group/item"
+                                      >
+                                        <div className="w-8 h-8 rounded-lg bg-amber-500/10 flex items-center justify-center text-amber-400 group-hover/item:bg-amber-500 group-hover/item:text-white transition-all">
+                                          <Tag size={14} />
+                                        </div>
+                                        <div className="flex flex-col items-start text-left">
+                                          <span className="font-bold">Details</span>
+                                          <span className="text-[10px] text-zinc-500">Tags and notes</span>
+                                        </div>
+                                      </button>
+                                      <button
+                                        onClick={(e) => {
+                                          e.stopPropagation();
+                                          setActiveMenuId(null);
+                                          setExportJob(null);
+                                          setExportingFile(rec);
+                                        }}
+                                        className="w-full flex items-center gap-3 px-3 py-2.5 text-sm text-zinc-300 hover:bg-white/10 hover:text-white rounded-xl transition-all group/item"
+                                      >
+                                        <div className="w-8 h-8 rounded-lg bg-emerald-500/10 flex items-center justify-center text-emerald-400 group-hover/item:bg-emerald-500 group-hover/item:text-white transition-all">
+                                          <Download size={14} />
+                                        </div>
+                                        <div className="flex flex-col items-start text-left">
+                                          <span className="font-bold">Export</span>
+                                          <span className="text-[10px] text-zinc-500">GIF, WebM, MP4, audio</span>
+                                        </div>
+                                      </button>
                                       <div className="my-1 h-px bg-white/5 mx-2" />
                                       <button
                                         onClick={(e) => {
@@ -827,6 +1199,14 @@ export default function App({ mode = 'controls' }: AppProps) {
                             </div>
                           );
                         })}
+                      {recordings.length < totalRecordings && (
+                        <button
+                          onClick={() => refreshRecordings(true)}
+                          className="col-span-full w-full py-2 text-xs font-bold text-zinc-400 hover:text-white bg-white/5 hover:bg-white/10 rounded-lg transition-colors"
+                        >
+                          Load more ({recordings.length} of {totalRecordings})
+                        </button>
+                      )}
                     </div>
                   )}
 
@@ -843,14 +1223,10 @@ export default function App({ mode = 'controls' }: AppProps) {
                           onChange={(e) => setNewName(e.target.value)}
                           onKeyDown={(e) => {
                             if (e.key === 'Enter') {
-                              const path = renamingFile.fullPath;
-                              if (path) {
-                                const folderPath = path.substring(0, path.lastIndexOf('\\'));
-                                invoke('rename_recording', { path: folderPath, newName }).then(() => {
-                                  setRenamingFile(null);
-                                  refreshRecordings();
-                                });
-                              }
+                              commands.renameRecording(renamingFile.folder, newName).then(() => {
+                                setRenamingFile(null);
+                                refreshRecordings();
+                              });
                             }
                             if (e.key === 'Escape') setRenamingFile(null);
                           }}
@@ -865,14 +1241,53 @@ export default function App({ mode = 'controls' }: AppProps) {
                           </button>
                           <button
                             onClick={() => {
-                              const path = renamingFile.fullPath;
-                              if (path) {
-                                const folderPath = path.substring(0, path.lastIndexOf('\\'));
-                                invoke('rename_recording', { path: folderPath, newName }).then(() => {
-                                  setRenamingFile(null);
-                                  refreshRecordings();
-                                });
-                              }
+                              commands.renameRecording(renamingFile.folder, newName).then(() => {
+                                setRenamingFile(null);
+                                refreshRecordings();
+                              });
+                            }}
+                            className="flex-1 px-4 py-3 bg-indigo-500 hover:bg-indigo-400 text-white rounded-xl font-bold shadow-lg shadow-indigo-500/20 transition-all"
+                          >
+                            Save Changes
+                          </button>
+                        </div>
+                      </div>
+                    </div>
+                  )}
+
+                  {/* Details Modal */}
+                  {editingDetails && (
+                    <div className="fixed inset-0 bg-black/60 backdrop-blur-sm z-[100] flex items-center justify-center p-4">
+                      <div className="bg-zinc-900 border border-white/10 rounded-3xl p-8 w-full max-w-md shadow-2xl animate-in fade-in zoom-in-95 duration-200">
+                        <h3 className="text-xl font-bold mb-2">Recording Details</h3>
+                        <p className="text-zinc-500 text-sm mb-6">Organize "{editingDetails.name}" with tags and notes.</p>
+                        <label className="block text-xs font-bold text-zinc-400 mb-2">Tags <span className="font-normal text-zinc-600">(comma separated)</span></label>
+                        <input
+                          autoFocus
+                          type="text"
+                          value={detailsTags}
+                          onChange={(e) => setDetailsTags(e.target.value)}
+                          placeholder="project-x, demo"
+                          className="w-full bg-black/20 border border-white/10 rounded-xl px-4 py-3 text-white focus:border-indigo-500 outline-none transition-all mb-4"
+                        />
+                        <label className="block text-xs font-bold text-zinc-400 mb-2">Notes</label>
+                        <textarea
+                          value={detailsNotes}
+                          onChange={(e) => setDetailsNotes(e.target.value)}
+                          rows={4}
+                          className="w-full bg-black/20 border border-white/10 rounded-xl px-4 py-3 text-white text-sm focus:border-indigo-500 outline-none transition-all mb-8 resize-none custom-scrollbar"
+                        />
+                        <div className="flex gap-3">
+                          <button
+                            onClick={() => setEditingDetails(null)}
+                            className="flex-1 px-4 py-3 bg-white/5 hover:bg-white/10 rounded-xl font-bold transition-all"
+                          >
+                            Cancel
+                          </button>
+                          <button
+                            onClick={() => {
+                              updateMetadata(editingDetails, { tags: detailsTags.split(','), notes: detailsNotes })
+                                .then(() => setEditingDetails(null));
                             }}
                             className="flex-1 px-4 py-3 bg-indigo-500 hover:bg-indigo-400 text-white rounded-xl font-bold shadow-lg shadow-indigo-500/20 transition-all"
                           >
@@ -883,6 +1298,86 @@ export default function App({ mode = 'controls' }: AppProps) {
                     </div>
                   )}
 
+                  {/* Export Modal */}
+                  {exportingFile && (
+                    <div className="fixed inset-0 bg-black/60 backdrop-blur-sm z-[100] flex items-center justify-center p-4">
+                      <div className="bg-zinc-900 border border-white/10 rounded-3xl p-8 w-full max-w-md shadow-2xl animate-in fade-in zoom-in-95 duration-200">
+                        <h3 className="text-xl font-bold mb-2">Export Recording</h3>
+                        <p className="text-zinc-500 text-sm mb-6">Files are saved to the <span className="text-white font-bold">exports</span> folder of "{exportingFile.name}".</p>
+                        {!exportJob ? (
+                          <div className="grid grid-cols-2 gap-2 mb-6">
+                            {EXPORT_PRESETS.map(preset => (
+                              <button
+                                key={preset.id}
+                                onClick={() => {
+                                  commands.exportRecording(exportingFile.folder, preset.id)
+                                    .then(job => setExportJob(job))
+                                    .catch(e => setExportJob({ id: '', kind: { type: 'export', sessionPath: exportingFile.folder, preset: preset.id }, status: 'failed', progress: 0, output: null, error: String(e), attempts: 0, createdAt: Date.now(), updatedAt: Date.now() }));
+                                }}
+                                className="flex flex-col items-start text-left px-4 py-3 bg-white/5 hover:bg-indigo-500/20 border border-white/5 hover:border-indigo-500/40 rounded-xl transition-all"
+                              >
+                                <span className="text-sm font-bold">{preset.label}</span>
+                                <span className="text-[10px] text-zinc-500">{preset.description}</span>
+                              </button>
+                            ))}
+                          </div>
+                        ) : (
+                          <div className="mb-6">
+                            <div className="flex justify-between text-xs font-bold mb-2">
+                              <span className="text-zinc-400">{EXPORT_PRESETS.find(p => 'preset' in exportJob.kind && p.id === exportJob.kind.preset)?.label}</span>
+                              <span className={exportJob.status === 'failed' ? 'text-red-400' : 'text-indigo-400'}>
+                                {exportJob.status === 'queued' ? 'Queued' : exportJob.status === 'running' ? `${Math.round(exportJob.progress * 100)}%` : exportJob.status === 'done' ? 'Done' : exportJob.status === 'cancelled' ? 'Cancelled' : 'Failed'}
+                              </span>
+                            </div>
+                            <div className="h-2 bg-white/5 rounded-full overflow-hidden">
+                              <div
+                                className={`h-full transition-all ${exportJob.status === 'failed' ? 'bg-red-500' : 'bg-indigo-500'}`}
+                                style={{ width: `${exportJob.status === 'done' ? 100 : Math.round(exportJob.progress * 100)}%` }}
+                              />
+                            </div>
+                            {exportJob.error && <p className="text-red-400 text-xs mt-3 break-words">{exportJob.error}</p>}
+                          </div>
+                        )}
+                        <div className="flex gap-3">
+                          {exportJob?.status === 'queued' || exportJob?.status === 'running' ? (
+                            <button
+                              onClick={() => commands.cancelJob(exportJob.id).catch(console.error)}
+                              className="flex-1 px-4 py-3 bg-white/5 hover:bg-white/10 rounded-xl font-bold transition-all"
+                            >
+                              Cancel Export
+                            </button>
+                          ) : (
+                            <button
+                              onClick={() => {
+                                setExportingFile(null);
+                                setExportJob(null);
+                              }}
+                              className="flex-1 px-4 py-3 bg-white/5 hover:bg-white/10 rounded-xl font-bold transition-all"
+                            >
+                              Close
+                            </button>
+                          )}
+                          {(exportJob?.status === 'failed' || exportJob?.status === 'cancelled') && exportJob.id && (
+                            <button
+                              onClick={() => commands.retryJob(exportJob.id).then(job => setExportJob(job)).catch(console.error)}
+                              className="flex-1 px-4 py-3 bg-indigo-500 hover:bg-indigo-400 text-white rounded-xl font-bold shadow-lg shadow-indigo-500/20 transition-all"
+                            >
+                              Retry
+                            </button>
+                          )}
+                          {exportJob?.status === 'done' && exportJob.output && (
+                            <button
+                              onClick={() => commands.openFolder(exportJob.output)}
+                              className="flex-1 px-4 py-3 bg-indigo-500 hover:bg-indigo-400 text-white rounded-xl font-bold shadow-lg shadow-indigo-500/20 transition-all"
+                            >
+                              Show File
+                            </button>
+                          )}
+                        </div>
+                      </div>
+                    </div>
+                  )}
+
                   {/* Delete Confirmation Modal */}
                   {deletingFile && (
                     <div className="fixed inset-0 bg-black/60 backdrop-blur-sm z-[100] flex items-center justify-center p-4">
@@ -892,7 +1387,7 @@ export default function App({ mode = 'controls' }: AppProps) {
                         </div>
                         <h3 className="text-xl font-bold mb-2">Delete Recording?</h3>
                         <p className="text-zinc-500 text-sm mb-8 leading-relaxed">
-                          This will permanently delete <span className="text-white font-bold">"{deletingFile.name}"</span> and all its associated files. This action cannot be undone.
+                          <span className="text-white font-bold">"{deletingFile.name}"</span> and all its associated files will be moved to the trash. You can restore it until the trash is emptied.
                         </p>
                         <div className="flex gap-3">
                           <button
@@ -903,23 +1398,89 @@ export default function App({ mode = 'controls' }: AppProps) {
                           </button>
                           <button
                             onClick={() => {
-                              const path = deletingFile.fullPath;
-                              if (path) {
-                                const folderPath = path.substring(0, path.lastIndexOf('\\'));
-                                invoke('delete_recording', { path: folderPath }).then(() => {
-                                  setDeletingFile(null);
-                                  refreshRecordings();
-                                });
-                              }
+                              moveToTrash([deletingFile]).then(() => setDeletingFile(null));
                             }}
                             className="flex-1 px-4 py-3 bg-red-500 hover:bg-red-400 text-white rounded-xl font-bold shadow-lg shadow-red-500/20 transition-all"
                           >
-                            Delete Forever
+                            Move to Trash
                           </button>
                         </div>
                       </div>
                     </div>
                   )}
+
+                  {/* Trash Modal */}
+                  {showTrash && (
+                    <div className="fixed inset-0 bg-black/60 backdrop-blur-sm z-[100] flex items-center justify-center p-4">
+                      <div className="bg-zinc-900 border border-white/10 rounded-3xl p-8 w-full max-w-lg shadow-2xl animate-in fade-in zoom-in-95 duration-200">
+                        <div className="flex items-center justify-between mb-2">
+                          <h3 className="text-xl font-bold">Trash</h3>
+                          <button onClick={() => setShowTrash(false)} className="p-1 text-zinc-500 hover:text-white">
+                            <X size={18} />
+                          </button>
+                        </div>
+                        <p className="text-zinc-500 text-xs mb-6">
+                          {settings.trashRetentionDays > 0
+                            ? `Recordings are deleted permanently after ${settings.trashRetentionDays} days.`
+                            : 'Recordings stay here until the trash is emptied.'}
+                        </p>
+                        {trashItems.length === 0 ? (
+                          <p className="text-zinc-500 text-sm text-center py-8">The trash is empty.</p>
+                        ) : (
+                          <div className="space-y-2 max-h-80 overflow-y-auto custom-scrollbar pr-2 mb-6">
+                            {trashItems.map(item => (
+                              <div key={item.id} className="flex items-center justify-between gap-3 bg-white/5 rounded-xl px-4 py-3">
+                                <div className="min-w-0">
+                                  <div className="text-sm font-bold truncate">{item.name}</div>
+                                  <div className="text-[10px] text-zinc-500">
+                                    Deleted {new Date(item.deletedAt).toLocaleString()} • {formatSize(item.sizeBytes)}
+                                  </div>
+                                </div>
+                                <button
+                                  onClick={() => restoreFromTrash([item.id])}
+                                  className="flex items-center gap-1.5 px-3 py-1.5 text-xs font-bold text-indigo-400 bg-indigo-500/10 hover:bg-indigo-500/20 rounded-lg shrink-0 transition-colors"
+                                >
+                                  <RotateCcw size={12} /> Restore
+                                </button>
+                              </div>
+                            ))}
+                          </div>
+                        )}
+                        {trashItems.length > 0 && (
+                          <button
+                            onClick={() => {
+                              if (window.confirm('Permanently delete everything in the trash? This cannot be undone.')) {
+                                commands.emptyTrash()
+                                  .then(refreshTrash)
+                                  .catch(e => alert(`Failed to empty trash: ${e}`));
+                              }
+                            }}
+                            className="w-full px-4 py-3 bg-red-500 hover:bg-red-400 text-white rounded-xl font-bold shadow-lg shadow-red-500/20 transition-all"
+                          >
+                            Empty Trash
+                          </button>
+                        )}
+                      </div>
+                    </div>
+                  )}
+
+                  {/* Undo Delete */}
+                  {trashUndo && trashUndo.length > 0 && (
+                    <div className="absolute bottom-6 left-1/2 -translate-x-1/2 z-50 flex items-center gap-4 bg-zinc-800 border border-white/10 rounded-xl px-4 py-2.5 shadow-2xl animate-in fade-in slide-in-from-bottom-2 duration-200">
+                      <span className="text-sm text-zinc-300">
+                        {trashUndo.length === 1 ? 'Recording moved to trash' : `${trashUndo.length} recordings moved to trash`}
+                      </span>
+                      <button
+                        onClick={() => {
+                          restoreFromTrash(trashUndo);
+                          setTrashUndo(null);
+                        }}
+                        className="text-sm font-bold text-indigo-400 hover:text-indigo-300"
+                      >
+                        Undo
+                      </button>
+                    </div>
+                  )}
                 </div>
               )}
 
@@ -930,6 +1491,9 @@ export default function App({ mode = 'controls' }: AppProps) {
                     settings={settings}
                     updateSettings={updateSettings}
                     availableDevices={availableDevices}
+                    profiles={profiles}
+                    refreshProfiles={refreshProfiles}
+                    currentOptions={currentRecordingOptions}
                   />
                 </div>
               )}
@@ -9,7 +9,7 @@ export default function WebcamOverlay() {
     const [isHovered, setIsHovered] = useState(false);
     const [hasStream, setHasStream] = useState(false);
     const [error, setError] = useState<string | null>(null);
-    const { settings, loaded } = useSettings();
+    const { settings } = useSettings();
     const streamRef = useRef<MediaStream | null>(null);
     const lastSizeRef = useRef({ w: 0, h: 0 });
 
@@ -22,8 +22,6 @@ export default function WebcamOverlay() {
     };
 
     const startCamera = async () => {
-        if (!loaded) return;
-
         console.log('WebcamOverlay: Attempting start with device:', settings.webcamDevice);
         stopStream();
         setHasStream(false);
@@ -73,16 +71,12 @@ export default function WebcamOverlay() {
 
     // React to settings changes
     useEffect(() => {
-        if (loaded) {
-            startCamera();
-        }
+        startCamera();
         return () => stopStream();
-    }, [loaded, settings.webcamDevice]);
+    }, [settings.webcamDevice]);
 
     // Handle window resizing and properties
     useEffect(() => {
-        if (!loaded) return;
-
         let frameId: number;
         const updateWindowProps = async () => {
             try {
@@ -116,14 +110,12 @@ export default function WebcamOverlay() {
         return () => {
             if (frameId) cancelAnimationFrame(frameId);
         };
-    }, [settings.webcamWidth, settings.webcamHeight, loaded]);
+    }, [settings.webcamWidth, settings.webcamHeight]);
 
     const getRoundnessStyle = () => {
         return `${settings.webcamRoundness / 2}%`;
     };
 
-    if (!loaded) return null;
-
     // NOTE: We don't check settings.webcamEnabled here. 
     // If the window is open, it should be working. 
     // This fixed the "Sync Hardware" requirement where the window 
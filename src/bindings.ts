@@ -0,0 +1,629 @@
+// @ts-nocheck
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+async startRecording(options: RecordingOptions) : Promise<string> {
+    return await TAURI_INVOKE("start_recording", { options });
+},
+async stopRecording() : Promise<StopResult> {
+    return await TAURI_INVOKE("stop_recording");
+},
+/**
+ * Starts recording with a stored profile. `name` names the recording.
+ */
+async startRecordingWithProfile(id: string, name: string | null) : Promise<string> {
+    return await TAURI_INVOKE("start_recording_with_profile", { id, name });
+},
+async listProfiles() : Promise<RecordingProfile[]> {
+    return await TAURI_INVOKE("list_profiles");
+},
+async createProfile(name: string, options: RecordingOptions) : Promise<RecordingProfile> {
+    return await TAURI_INVOKE("create_profile", { name, options });
+},
+async updateProfile(id: string, name: string, options: RecordingOptions) : Promise<RecordingProfile> {
+    return await TAURI_INVOKE("update_profile", { id, name, options });
+},
+async deleteProfile(id: string) : Promise<null> {
+    return await TAURI_INVOKE("delete_profile", { id });
+},
+/**
+ * JSON document with the given profiles, or all of them.
+ */
+async exportProfiles(ids: string[] | null) : Promise<string> {
+    return await TAURI_INVOKE("export_profiles", { ids });
+},
+async importProfiles(json: string) : Promise<RecordingProfile[]> {
+    return await TAURI_INVOKE("import_profiles", { json });
+},
+/**
+ * Starts capturing into a rolling buffer that keeps the last `seconds`
+ * (two minutes by default) until `save_replay` writes them out.
+ */
+async startReplayBuffer(options: RecordingOptions, seconds: number | null) : Promise<ReplayStatus> {
+    return await TAURI_INVOKE("start_replay_buffer", { options, seconds });
+},
+async stopReplayBuffer() : Promise<null> {
+    return await TAURI_INVOKE("stop_replay_buffer");
+},
+async getReplayStatus() : Promise<ReplayStatus> {
+    return await TAURI_INVOKE("get_replay_status");
+},
+/**
+ * Saves the last `seconds` of the replay buffer (all of it by default) as a
+ * new recording and returns its session folder.
+ */
+async saveReplay(seconds: number | null, name: string | null) : Promise<string> {
+    return await TAURI_INVOKE("save_replay", { seconds, name });
+},
+async getInputDevices() : Promise<DeviceList> {
+    return await TAURI_INVOKE("get_input_devices");
+},
+async toggleWebcam(show: boolean) : Promise<null> {
+    return await TAURI_INVOKE("toggle_webcam", { show });
+},
+/**
+ * Opens the save folder, or the folder at/containing `path` inside it.
+ */
+async openFolder(path: string | null) : Promise<null> {
+    return await TAURI_INVOKE("open_folder", { path });
+},
+async openFile(path: string) : Promise<null> {
+    return await TAURI_INVOKE("open_file", { path });
+},
+async listRecordings(query: ListQuery | null) : Promise<RecordingPage> {
+    return await TAURI_INVOKE("list_recordings", { query });
+},
+async searchRecordings(query: string, limit: number | null) : Promise<SearchResult[]> {
+    return await TAURI_INVOKE("search_recordings", { query, limit });
+},
+/**
+ * Starts watching the save folder, replacing the watcher of a previous one.
+ */
+async watchLibrary() : Promise<null> {
+    return await TAURI_INVOKE("watch_library");
+},
+/**
+ * Moves the session folder into the trash and returns the id that
+ * `restore_recording` takes to undo it.
+ */
+async deleteRecording(id: string) : Promise<string> {
+    return await TAURI_INVOKE("delete_recording", { id });
+},
+async listTrash() : Promise<TrashedRecording[]> {
+    return await TAURI_INVOKE("list_trash");
+},
+/**
+ * Returns the restored session folder.
+ */
+async restoreRecording(id: string) : Promise<string> {
+    return await TAURI_INVOKE("restore_recording", { id });
+},
+async emptyTrash() : Promise<null> {
+    return await TAURI_INVOKE("empty_trash");
+},
+/**
+ * Dry run: what enforcing `policy` (default: the saved one) would remove.
+ */
+async previewRetention(policy: RetentionPolicy | null) : Promise<RetentionCandidate[]> {
+    return await TAURI_INVOKE("preview_retention", { policy });
+},
+/**
+ * Enforces `policy` (default: the saved one) now and returns what it removed.
+ */
+async applyRetention(policy: RetentionPolicy | null) : Promise<RetentionCandidate[]> {
+    return await TAURI_INVOKE("apply_retention", { policy });
+},
+async renameRecording(id: string, newName: string) : Promise<null> {
+    return await TAURI_INVOKE("rename_recording", { id, newName });
+},
+/**
+ * Updates whichever of tags, notes and favorite are given; omitted fields
+ * are left as they are.
+ */
+async updateRecordingMetadata(id: string, tags: string[] | null, notes: string | null, favorite: boolean | null) : Promise<null> {
+    return await TAURI_INVOKE("update_recording_metadata", { id, tags, notes, favorite });
+},
+/**
+ * Every tag used in the library with the number of recordings carrying it,
+ * most used first.
+ */
+async listTags() : Promise<TagCount[]> {
+    return await TAURI_INVOKE("list_tags");
+},
+async startGlobalListener() : Promise<void> {
+    await TAURI_INVOKE("start_global_listener");
+},
+/**
+ * Space on the volume holding the save folder.
+ */
+async getDiskInfo() : Promise<DiskInfo> {
+    return await TAURI_INVOKE("get_disk_info");
+},
+/**
+ * Lets the user pick a new save folder. It becomes the folder commands are
+ * confined to once it is saved as `savePath`.
+ */
+async selectFolder() : Promise<string | null> {
+    return await TAURI_INVOKE("select_folder");
+},
+async getHotkeys() : Promise<HotkeyBindings> {
+    return await TAURI_INVOKE("get_hotkeys");
+},
+async getSettings() : Promise<AppSettings> {
+    return await TAURI_INVOKE("get_settings");
+},
+/**
+ * Applies `changes`, a subset of the settings keyed like `AppSettings`,
+ * and broadcasts the result to every window as `settings-changed`.
+ */
+async updateSettings(changes: Partial<{ [key in string]: JsonValue }>) : Promise<AppSettings> {
+    return await TAURI_INVOKE("update_settings", { changes });
+},
+async addMarker(label: string | null) : Promise<Marker> {
+    return await TAURI_INVOKE("add_marker", { label });
+},
+/**
+ * Queues a trim of the recording down to `ranges`; the job's output is the
+ * trimmed file.
+ */
+async trimRecording(id: string, ranges: KeepRange[], precise: boolean | null) : Promise<Job> {
+    return await TAURI_INVOKE("trim_recording", { id, ranges, precise });
+},
+/**
+ * Queues silence detection; `get_silence_analysis` returns the result once
+ * the job is done.
+ */
+async detectSilence(id: string, thresholdDb: number | null, minDurationMs: number | null) : Promise<Job> {
+    return await TAURI_INVOKE("detect_silence", { id, thresholdDb, minDurationMs });
+},
+/**
+ * The latest silence analysis of the recording, if any.
+ */
+async getSilenceAnalysis(id: string) : Promise<SilenceAnalysis | null> {
+    return await TAURI_INVOKE("get_silence_analysis", { id });
+},
+/**
+ * Queues an export without silence. `mode` is "remove" to cut silent
+ * stretches out or "speedup" to play them at `speed` (default 4x); the
+ * job's output is the new file.
+ */
+async exportWithoutSilence(id: string, mode: string, speed: number | null, thresholdDb: number | null, minDurationMs: number | null) : Promise<Job> {
+    return await TAURI_INVOKE("export_without_silence", { id, mode, speed, thresholdDb, minDurationMs });
+},
+/**
+ * Queues an export of the recording with `preset`; progress arrives as
+ * `job-progress` events for the returned job.
+ */
+async exportRecording(id: string, preset: ExportPreset) : Promise<Job> {
+    return await TAURI_INVOKE("export_recording", { id, preset });
+},
+/**
+ * Queues a local Whisper transcription. `model` is a file name inside the
+ * app's models folder or an absolute path; defaults to the base model.
+ */
+async transcribeRecording(id: string, model: string | null, language: string | null, muxSubtitles: boolean | null) : Promise<Job> {
+    return await TAURI_INVOKE("transcribe_recording", { id, model, language, muxSubtitles });
+},
+async getTranscript(id: string) : Promise<Transcript | null> {
+    return await TAURI_INVOKE("get_transcript", { id });
+},
+async listJobs() : Promise<Job[]> {
+    return await TAURI_INVOKE("list_jobs");
+},
+async cancelJob(id: string) : Promise<null> {
+    return await TAURI_INVOKE("cancel_job", { id });
+},
+async retryJob(id: string) : Promise<Job> {
+    return await TAURI_INVOKE("retry_job", { id });
+}
+}
+
+/** user-defined events **/
+
+
+export const events = __makeEvents__<{
+globalClick: GlobalClick,
+globalKey: GlobalKey,
+hotkeyAction: HotkeyAction,
+jobProgress: JobProgress,
+libraryChanged: LibraryChanged,
+lowDiskWarning: LowDiskWarning,
+markerAdded: MarkerAdded,
+recordingStarted: RecordingStarted,
+recordingStopped: RecordingStopped,
+replayBufferChanged: ReplayBufferChanged,
+settingsChanged: SettingsChanged
+}>({
+globalClick: "global-click",
+globalKey: "global-key",
+hotkeyAction: "hotkey-action",
+jobProgress: "job-progress",
+libraryChanged: "library-changed",
+lowDiskWarning: "low-disk-warning",
+markerAdded: "marker-added",
+recordingStarted: "recording-started",
+recordingStopped: "recording-stopped",
+replayBufferChanged: "replay-buffer-changed",
+settingsChanged: "settings-changed"
+})
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+/**
+ * Everything the settings panel configures. Missing fields take their
+ * defaults, so older files and partial documents always load.
+ */
+export type AppSettings = { micEnabled: boolean; micDevice: string; systemAudioEnabled: boolean; webcamEnabled: boolean; captureMode: CaptureMode; showClicks: boolean; showKeystrokes: boolean; keystrokePosition: KeystrokePosition; 
+/**
+ * `#rrggbb` color of left-click ripples.
+ */
+clickColorLeft: string; clickColorRight: string; micVolume: number; systemAudioVolume: number; 
+/**
+ * Empty for the default `Videos/Reframe`.
+ */
+savePath: string; 
+/**
+ * Days before trashed recordings are deleted for good; 0 keeps them.
+ */
+trashRetentionDays: number; retention: RetentionPolicy; 
+/**
+ * Recordings stop when free space on the save drive falls below this.
+ */
+lowDiskThresholdMb: number; 
+/**
+ * Split recordings into parts every this many minutes.
+ */
+segmentMinutes: number | null; 
+/**
+ * Split recordings into parts of roughly this size.
+ */
+segmentSizeMb: number | null; 
+/**
+ * How much the replay buffer keeps, in seconds.
+ */
+replayBufferSeconds: number; webcamShape: WebcamShape; webcamZoom: number; webcamRoundness: number; webcamWidth: number; webcamHeight: number; webcamDevice: string; 
+/**
+ * A preset label such as "1080p FHD (1920x1080)"; labels without a
+ * size record at the source resolution.
+ */
+resolution: string; 
+/**
+ * How the capture is fitted into the resolution; `None` picks by
+ * capture mode.
+ */
+scaleMode: ScaleMode | null; fps: number; encoder: VideoEncoder; container: Container; hotkeys: HotkeyBindings }
+export type CaptureMode = "fullscreen" | "window" | "region"
+export type ClickPayload = { x: number; y: number; button: string }
+/**
+ * File format recordings are written in.
+ */
+export type Container = "mp4" | 
+/**
+ * Matroska stays playable when the app or FFmpeg dies mid-recording.
+ */
+"mkv"
+export type DeviceList = { audio: string[]; video: string[] }
+export type DiskInfo = { 
+/**
+ * Bytes available to this user, which honours quotas.
+ */
+free: number; total: number; label: string }
+export type ExportPreset = "gif" | "webm" | "mp4-720p" | "mp4-1080p" | 
+/**
+ * 9:16 center crop for shorts/reels.
+ */
+"vertical" | "mp3" | "opus"
+export type FileRecord = { 
+/**
+ * The session folder name, unique within the save root.
+ */
+id: string; name: string; 
+/**
+ * Milliseconds since the Unix epoch.
+ */
+createdAt: number; durationMs: number; sizeBytes: number; width: number; height: number; fps: number; bitrate: number; videoCodec: string | null; audioTracks: TrackInfo[]; folder: string; files: string[]; fullPath: string; thumbnail: string | null; previewSprite: string | null; previewFrames: number; tags: string[]; notes: string; favorite: boolean }
+/**
+ * A mouse click anywhere on screen, for the overlay's ripples.
+ */
+export type GlobalClick = ClickPayload
+/**
+ * A key press anywhere, named as `rdev` debug-prints it, e.g. "KeyA".
+ */
+export type GlobalKey = string
+/**
+ * Actions that can be bound to a global hotkey. Sent as the
+ * `hotkey-action` event for the ones the dashboard carries out.
+ */
+export type HotkeyAction = "startRecording" | "stopRecording" | "togglePause" | "addMarker" | "toggleWebcam"
+/**
+ * Hotkey bindings as stored in the app settings. An empty string disables
+ * the binding.
+ */
+export type HotkeyBindings = { startRecording: string; stopRecording: string; togglePause: string; addMarker: string; toggleWebcam: string }
+export type Job = { id: string; kind: JobKind; status: JobStatus; 
+/**
+ * 0.0-1.0 while running.
+ */
+progress: number; output: string | null; error: string | null; attempts: number; 
+/**
+ * Milliseconds since the Unix epoch.
+ */
+createdAt: number; updatedAt: number }
+/**
+ * Post-processing work that runs off the command thread.
+ */
+export type JobKind = { type: "export"; sessionPath: string; preset: ExportPreset } | { type: "thumbnails"; sessionPath: string } | { type: "transcribe"; sessionPath: string; modelPath: string; language: string | null; muxSubtitles: boolean } | { type: "trim"; sessionPath: string; ranges: KeepRange[]; precise: boolean } | { type: "detectSilence"; sessionPath: string; thresholdDb: number; minDurationMs: number } | 
+/**
+ * Cuts silent stretches out, or plays them at `silent_speed`.
+ */
+{ type: "removeSilence"; sessionPath: string; thresholdDb: number; minDurationMs: number; silentSpeed: number | null }
+/**
+ * Sent whenever a job is queued, makes progress or finishes.
+ */
+export type JobProgress = Job
+export type JobStatus = "queued" | "running" | "done" | "failed" | "cancelled"
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+/**
+ * A span of the source recording to keep, in milliseconds.
+ */
+export type KeepRange = { startMs: number; endMs: number }
+/**
+ * Screen corner (or bottom edge) the keystroke overlay sits in.
+ */
+export type KeystrokePosition = "bottom-left" | "bottom-right" | "top-left" | "top-right" | "bottom-center"
+export type LibraryChanged = { folder: string; path: string; 
+/**
+ * False when the session folder was deleted or renamed away.
+ */
+exists: boolean }
+/**
+ * Sorting, filtering and paging for `list_recordings`. Every field is
+ * optional; the default is newest first, unfiltered, everything.
+ */
+export type ListQuery = { sort: SortKey; 
+/**
+ * Defaults to newest/longest/largest first and A-Z for names.
+ */
+ascending: boolean | null; 
+/**
+ * Creation time bounds in milliseconds since the Unix epoch, inclusive.
+ */
+from: number | null; to: number | null; minDurationMs: number | null; tag: string | null; 
+/**
+ * Container extension ("mp4") or video codec ("h264").
+ */
+format: string | null; favoritesOnly: boolean; 
+/**
+ * Restricts the listing to these session folders, e.g. search hits.
+ */
+folders: string[] | null; offset: number; limit: number | null }
+export type LowDiskWarning = { freeBytes: number; 
+/**
+ * Estimated recording time left before the low-disk stop.
+ */
+minutesRemaining: number }
+/**
+ * A point of interest dropped while recording, relative to the start of the
+ * capture.
+ */
+export type Marker = { timeMs: number; label: string }
+export type MarkerAdded = Marker
+export type RecordingOptions = { name?: string | null; micEnabled: boolean; micDevice: string | null; systemAudioEnabled: boolean; 
+/**
+ * Picks the default scale mode. Not wired into the FFmpeg input yet,
+ * like the window title and region below.
+ */
+captureMode: string | null; windowTitle: string | null; region: string | null; micVolume: number | null; systemAudioVolume: number | null; framerate: number | null; width: number | null; height: number | null; 
+/**
+ * How the capture is fitted into `width`x`height`; by default it
+ * depends on the capture mode.
+ */
+scaleMode?: ScaleMode | null; 
+/**
+ * Start a new part every this many minutes.
+ */
+segmentMinutes: number | null; 
+/**
+ * Start a new part at roughly this size.
+ */
+segmentSizeMb: number | null; encoder?: VideoEncoder; container?: Container; 
+/**
+ * Extra video filter chain applied after scaling.
+ */
+filters?: string | null }
+export type RecordingPage = { items: FileRecord[]; 
+/**
+ * Matches before paging.
+ */
+total: number; offset: number }
+/**
+ * Named capture settings: target, audio sources, encoder, container,
+ * frame rate, resolution and filters.
+ */
+export type RecordingProfile = { id: string; name: string; options: RecordingOptions }
+export type RecordingStarted = { sessionPath: string; trigger: string }
+export type RecordingStopped = { path: string; size: string; reason: string }
+export type ReplayBufferChanged = ReplayStatus
+export type ReplayStatus = { running: boolean; bufferSeconds: number }
+/**
+ * What happens to recordings retention removes.
+ */
+export type RetentionAction = 
+/**
+ * Move to the trash, which purges it after its own retention period.
+ */
+"trash" | "archive"
+export type RetentionCandidate = { id: string; name: string; createdAt: number; sizeBytes: number; 
+/**
+ * "count", "age" or "size": the first rule the recording falls foul of.
+ */
+reason: string }
+/**
+ * Cleanup rules from the `retention` setting. Every limit is optional;
+ * favorites and the newest recording are never removed.
+ */
+export type RetentionPolicy = { 
+/**
+ * Keep only this many of the newest recordings.
+ */
+keepLast: number | null; 
+/**
+ * Remove the oldest recordings until the library fits in this many GB.
+ * The trash and archive don't count; the trash is purged on its own.
+ */
+maxTotalGb: number | null; 
+/**
+ * Remove recordings older than this.
+ */
+maxAgeDays: number | null; action: RetentionAction }
+/**
+ * How a capture is fitted into the requested output size. The source size
+ * isn't known before FFmpeg opens it, so every mode is expressed in filter
+ * expressions that adapt to it.
+ */
+export type ScaleMode = 
+/**
+ * Scale to fit inside the size and letterbox the rest in black.
+ */
+"fit" | 
+/**
+ * Scale to cover the size and crop what overflows.
+ */
+"fill" | 
+/**
+ * Shrink to fit inside the size but never enlarge; the output keeps the
+ * source's aspect ratio instead of being padded.
+ */
+"fitWithin" | 
+/**
+ * Stretch to exactly the size, ignoring the aspect ratio.
+ */
+"exact"
+export type SearchMatch = { 
+/**
+ * "name", "tag", "marker", "notes" or "transcript".
+ */
+field: string; text: string; 
+/**
+ * Position inside the recording for marker and transcript hits.
+ */
+timeMs: number | null }
+export type SearchResult = { folder: string; path: string; name: string; score: number; matches: SearchMatch[] }
+/**
+ * Sent with the new settings after every change, so all windows follow.
+ */
+export type SettingsChanged = AppSettings
+/**
+ * Result of a `silencedetect` pass, cached in the session manifest together
+ * with the parameters it was run with.
+ */
+export type SilenceAnalysis = { thresholdDb: number; minDurationMs: number; ranges: SilentRange[]; totalSilentMs: number; analyzedAt: number }
+/**
+ * A stretch of the recording where every audio track stayed below the
+ * threshold, in milliseconds.
+ */
+export type SilentRange = { startMs: number; endMs: number }
+export type SortKey = "date" | "name" | "duration" | "size"
+export type StopResult = { path: string; size: string }
+export type TagCount = { name: string; count: number }
+export type TrackInfo = { index: number; kind: string; codec: string; channels?: number | null; sampleRate?: number | null; bitrate?: number | null }
+export type Transcript = { model: string; 
+/**
+ * ISO 639-1 code, or "auto" when Whisper detected it.
+ */
+language: string; createdAt: number; segments: TranscriptSegment[] }
+export type TranscriptSegment = { startMs: number; endMs: number; text: string }
+export type TrashedRecording = { 
+/**
+ * Folder name inside the trash; identifies the entry for restoring.
+ */
+id: string; folder: string; name: string; deletedAt: number; sizeBytes: number }
+/**
+ * H.264 encoder used while recording. Hardware encoders need a matching GPU
+ * and driver; FFmpeg fails to start without one.
+ */
+export type VideoEncoder = 
+/**
+ * Software x264, which works everywhere.
+ */
+"x264" | 
+/**
+ * NVIDIA NVENC.
+ */
+"nvenc" | 
+/**
+ * Intel Quick Sync.
+ */
+"qsv" | 
+/**
+ * AMD AMF.
+ */
+"amf"
+export type WebcamShape = "square" | "portrait" | "landscape"
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
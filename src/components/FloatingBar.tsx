@@ -6,7 +6,8 @@ import {
     Minus,
     GripVertical,
     X,
-    Video
+    Video,
+    Flag
 } from 'lucide-react';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import RecordingTimer from './RecordingTimer';
@@ -22,6 +23,8 @@ interface FloatingBarProps {
     initialSeconds?: number;
     webcamEnabled: boolean;
     toggleWebcam: () => void;
+    onAddMarker: () => void;
+    markerCount?: number;
 }
 
 const FloatingBar = ({
@@ -34,7 +37,9 @@ const FloatingBar = ({
     onTimeUpdate,
     initialSeconds = 0,
     webcamEnabled,
-    toggleWebcam
+    toggleWebcam,
+    onAddMarker,
+    markerCount = 0
 }: FloatingBarProps) => {
     return (
         <div
@@ -82,6 +87,17 @@ const FloatingBar = ({
             <div className="h-6 w-px bg-white/10" />
 
             <div className="flex items-center gap-3 text-zinc-400">
+                <button
+                    onPointerDown={(e) => e.stopPropagation()}
+                    onClick={onAddMarker}
+                    className="relative text-zinc-500 hover:text-indigo-400 transition-colors"
+                    title="Drop Chapter Marker"
+                >
+                    <Flag size={18} />
+                    {markerCount > 0 && (
+                        <span className="absolute -top-1.5 -right-2 text-[9px] font-bold text-indigo-300">{markerCount}</span>
+                    )}
+                </button>
                 <button
                     onPointerDown={(e) => e.stopPropagation()}
                     onClick={toggleWebcam}
@@ -1,4 +1,5 @@
 import { memo, useEffect, useState, useRef } from 'react';
+import { events } from '../bindings';
 
 interface InteractionLayerProps {
   mouseEnabled: boolean;
@@ -39,15 +40,12 @@ const InteractionLayer = memo(({
     let isMounted = true;
 
     const setupListener = async () => {
-      // Import dynamically to avoid SSR issues if any, though not applicable here
-      const { listen } = await import('@tauri-apps/api/event');
-
       if (mouseListenerRef.current) {
         mouseListenerRef.current();
         mouseListenerRef.current = null;
       }
 
-      const unlisten = await listen<{ x: number, y: number, button: string }>('global-click', (event) => {
+      const unlisten = await events.globalClick.listen((event) => {
         if (!isMounted) return;
         const { x, y, button } = event.payload;
         const id = Date.now();
@@ -86,14 +84,12 @@ const InteractionLayer = memo(({
     let isMounted = true;
 
     const setupListener = async () => {
-      const { listen } = await import('@tauri-apps/api/event');
-
       if (listenerRef.current) {
         listenerRef.current();
         listenerRef.current = null;
       }
 
-      const unlisten = await listen<string>('global-key', (event) => {
+      const unlisten = await events.globalKey.listen((event) => {
         if (!isMounted) return;
 
         // Raw key string like "KeyA", "Space", "ShiftLeft"
@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react';
-import { invoke, convertFileSrc } from '@tauri-apps/api/core';
+import { convertFileSrc } from '@tauri-apps/api/core';
 
 import {
     X,
@@ -10,7 +10,8 @@ import {
     FolderOpen,
     ExternalLink
 } from 'lucide-react';
-import type { FileRecord } from '../App';
+import { commands } from '../bindings';
+import type { FileRecord } from '../bindings';
 
 interface PlaybackModalProps {
     file: FileRecord;
@@ -24,7 +25,7 @@ const PlaybackModal = ({ file, onClose }: PlaybackModalProps) => {
 
     const openInSystemPlayer = () => {
         if (file.fullPath) {
-            invoke('open_file', { path: file.fullPath })
+            commands.openFile(file.fullPath)
                 .catch((err) => console.error('Failed to open file in system player:', err));
         }
     };
@@ -127,7 +128,7 @@ const PlaybackModal = ({ file, onClose }: PlaybackModalProps) => {
                         <button
                             onClick={() => {
                                 if (file.fullPath) {
-                                    invoke('open_folder', { path: file.fullPath }).catch((err) => console.error('Failed to open folder:', err));
+                                    commands.openFolder(file.fullPath).catch((err) => console.error('Failed to open folder:', err));
                                 }
                             }}
                             className="px-4 py-2 bg-white/5 hover:bg-white/10 border border-white/5 rounded-lg text-sm font-medium text-white transition-colors flex items-center gap-2"
@@ -0,0 +1,43 @@
+import { useState } from 'react';
+import { convertFileSrc } from '@tauri-apps/api/core';
+
+interface RecordingThumbnailProps {
+    thumbnail: string | null;
+    previewSprite: string | null;
+    previewFrames: number;
+}
+
+// Poster image that scrubs through the preview strip while hovering
+const RecordingThumbnail = ({ thumbnail, previewSprite, previewFrames }: RecordingThumbnailProps) => {
+    const [frame, setFrame] = useState<number | null>(null);
+
+    if (!thumbnail && !previewSprite) return null;
+
+    return (
+        <div
+            className="absolute inset-0"
+            onMouseMove={(e) => {
+                if (!previewSprite) return;
+                const rect = e.currentTarget.getBoundingClientRect();
+                const ratio = Math.min(0.999, Math.max(0, (e.clientX - rect.left) / rect.width));
+                setFrame(Math.floor(ratio * previewFrames));
+            }}
+            onMouseLeave={() => setFrame(null)}
+        >
+            {frame !== null && previewSprite ? (
+                <div
+                    className="w-full h-full bg-no-repeat"
+                    style={{
+                        backgroundImage: `url(${convertFileSrc(previewSprite)})`,
+                        backgroundSize: `${previewFrames * 100}% 100%`,
+                        backgroundPosition: `${previewFrames > 1 ? (frame / (previewFrames - 1)) * 100 : 0}% 0`,
+                    }}
+                />
+            ) : thumbnail && (
+                <img src={convertFileSrc(thumbnail)} alt="" className="w-full h-full object-cover" draggable={false} />
+            )}
+        </div>
+    );
+};
+
+export default RecordingThumbnail;
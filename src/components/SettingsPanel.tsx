@@ -1,20 +1,115 @@
-import { memo } from 'react';
+import { memo, useState, useRef, KeyboardEvent } from 'react';
 import {
     Monitor, Mic, Video, MousePointer2, Keyboard,
-    Info, Check, AlertCircle, Folder
+    Info, AlertCircle, Folder
 } from 'lucide-react';
-import { AppSettings } from '../hooks/useSettings';
+import { AppSettings, HotkeyBindings, RetentionPolicy } from '../hooks/useSettings';
 import Toggle from './Toggle';
-import { invoke } from '@tauri-apps/api/core';
 import { CustomSelect } from './CustomSelect';
+import { commands } from '../bindings';
+import type { RecordingOptions, RecordingProfile, RetentionCandidate } from '../bindings';
 
 interface SettingsPanelProps {
     settings: AppSettings;
     updateSettings: (newSettings: Partial<AppSettings>, immediate?: boolean) => Promise<void>;
     availableDevices: { audio: string[], video: string[] };
+    profiles: RecordingProfile[];
+    refreshProfiles: () => void;
+    /** Recording options built from the current settings. */
+    currentOptions: () => RecordingOptions;
 }
 
-export const SettingsPanel = memo(({ settings, updateSettings, availableDevices }: SettingsPanelProps) => {
+const HOTKEY_LABELS: { id: keyof HotkeyBindings, label: string }[] = [
+    { id: 'startRecording', label: 'Start Recording' },
+    { id: 'stopRecording', label: 'Stop Recording' },
+    { id: 'togglePause', label: 'Pause / Resume' },
+    { id: 'addMarker', label: 'Drop Marker' },
+    { id: 'toggleWebcam', label: 'Toggle Webcam' },
+];
+
+// Keyed by `RetentionCandidate['reason']`
+const RETENTION_REASONS: Record<string, string> = {
+    count: 'beyond the newest kept',
+    age: 'too old',
+    size: 'over the size limit',
+};
+
+// Turns a keydown into the "Ctrl+Shift+R" notation understood by the backend
+const formatHotkey = (e: KeyboardEvent): string | null => {
+    if (['Control', 'Shift', 'Alt', 'Meta'].includes(e.key)) return null;
+    const parts: string[] = [];
+    if (e.ctrlKey) parts.push('Ctrl');
+    if (e.altKey) parts.push('Alt');
+    if (e.shiftKey) parts.push('Shift');
+    if (e.metaKey) parts.push('Meta');
+    if (/^Key[A-Z]$/.test(e.code)) parts.push(e.code.slice(3));
+    else if (/^Digit[0-9]$/.test(e.code)) parts.push(e.code.slice(5));
+    else if (e.key === ' ') parts.push('Space');
+    else if (e.key.startsWith('Arrow')) parts.push(e.key.slice(5));
+    else parts.push(e.key.length === 1 ? e.key.toUpperCase() : e.key);
+    return parts.join('+');
+};
+
+export const SettingsPanel = memo(({ settings, updateSettings, availableDevices, profiles, refreshProfiles, currentOptions }: SettingsPanelProps) => {
+    const [hotkeyError, setHotkeyError] = useState<string | null>(null);
+    const [profileName, setProfileName] = useState('');
+    const [profileError, setProfileError] = useState<string | null>(null);
+    const importInputRef = useRef<HTMLInputElement>(null);
+
+    const runProfileCommand = async (command: Promise<unknown>) => {
+        try {
+            await command;
+            setProfileError(null);
+            refreshProfiles();
+        } catch (e) {
+            setProfileError(String(e));
+        }
+    };
+
+    const saveProfile = async () => {
+        const existing = profiles.find(p => p.name.toLowerCase() === profileName.trim().toLowerCase());
+        const options = { ...currentOptions(), name: null };
+        await runProfileCommand(existing
+            ? commands.updateProfile(existing.id, existing.name, options)
+            : commands.createProfile(profileName, options));
+        setProfileName('');
+    };
+
+    const exportProfiles = async () => {
+        try {
+            const json = await commands.exportProfiles(null);
+            const url = URL.createObjectURL(new Blob([json], { type: 'application/json' }));
+            const link = document.createElement('a');
+            link.href = url;
+            link.download = 'reframe-profiles.json';
+            link.click();
+            URL.revokeObjectURL(url);
+        } catch (e) {
+            setProfileError(String(e));
+        }
+    };
+
+    const importProfiles = async (file: File) => {
+        await runProfileCommand(file.text().then(json => commands.importProfiles(json)));
+    };
+    const [cleanupPreview, setCleanupPreview] = useState<RetentionCandidate[] | null>(null);
+
+    // Selects use 0 for "off", the policy uses null
+    const changeRetention = (changes: Partial<RetentionPolicy>) => {
+        updateSettings({ retention: { ...settings.retention, ...changes } });
+        setCleanupPreview(null);
+    };
+
+    const changeHotkey = async (id: keyof HotkeyBindings, value: string) => {
+        const hotkeys = { ...settings.hotkeys, [id]: value };
+        try {
+            await updateSettings({ hotkeys }, true);
+            setHotkeyError(null);
+        } catch (e) {
+            setHotkeyError(String(e));
+        }
+    };
+
     return (
         <div className="space-y-8 pb-20 max-w-4xl">
             {/* --- STORAGE & FILES --- */}
@@ -25,7 +120,7 @@ export const SettingsPanel = memo(({ settings, updateSettings, availableDevices
                         <label className="text-sm font-medium text-zinc-300">Save Location</label>
                         <button
                             onClick={async () => {
-                                const folder = await invoke<string | null>('select_folder');
+                                const folder = await commands.selectFolder();
                                 if (folder) updateSettings({ savePath: folder });
                             }}
                             className="text-xs bg-indigo-500/10 hover:bg-indigo-500/20 text-indigo-400 px-3 py-1.5 rounded-lg border border-indigo-500/20 transition-colors"
@@ -39,6 +134,147 @@ export const SettingsPanel = memo(({ settings, updateSettings, availableDevices
                         </span>
                         <Folder size={14} className="text-zinc-600 group-hover:text-zinc-400 shrink-0" />
                     </div>
+                    <CustomSelect
+                        label="Empty Trash After"
+                        options={[
+                            { value: 7, label: '7 days' },
+                            { value: 30, label: '30 days' },
+                            { value: 90, label: '90 days' },
+                            { value: 0, label: 'Never' },
+                        ]}
+                        value={settings.trashRetentionDays}
+                        onChange={(val) => updateSettings({ trashRetentionDays: val })}
+                    />
+                    <CustomSelect
+                        label="Stop Recording When Free Space Drops Below"
+                        options={[
+                            { value: 256, label: '256 MB' },
+                            { value: 512, label: '512 MB' },
+                            { value: 1024, label: '1 GB' },
+                            { value: 2048, label: '2 GB' },
+                            { value: 5120, label: '5 GB' },
+                        ]}
+                        value={settings.lowDiskThresholdMb}
+                        onChange={(val) => updateSettings({ lowDiskThresholdMb: val })}
+                    />
+                    <div className="grid grid-cols-2 gap-4">
+                        <CustomSelect
+                            label="Split Recordings Every"
+                            options={[
+                                { value: 0, label: 'Never' },
+                                { value: 15, label: '15 minutes' },
+                                { value: 30, label: '30 minutes' },
+                                { value: 60, label: '1 hour' },
+                                { value: 120, label: '2 hours' },
+                            ]}
+                            value={settings.segmentMinutes ?? 0}
+                            onChange={(val) => updateSettings({ segmentMinutes: val || null })}
+                        />
+                        <CustomSelect
+                            label="Split Recordings At"
+                            options={[
+                                { value: 0, label: 'No size limit' },
+                                { value: 1024, label: '~1 GB' },
+                                { value: 2048, label: '~2 GB' },
+                                { value: 4096, label: '~4 GB' },
+                            ]}
+                            value={settings.segmentSizeMb ?? 0}
+                            onChange={(val) => updateSettings({ segmentSizeMb: val || null })}
+                        />
+                    </div>
+                    <CustomSelect
+                        label="Replay Buffer Length"
+                        options={[
+                            { value: 30, label: '30 seconds' },
+                            { value: 60, label: '1 minute' },
+                            { value: 120, label: '2 minutes' },
+                            { value: 300, label: '5 minutes' },
+                            { value: 600, label: '10 minutes' },
+                        ]}
+                        value={settings.replayBufferSeconds}
+                        onChange={(val) => updateSettings({ replayBufferSeconds: val })}
+                    />
+                </div>
+            </section>
+
+            {/* --- AUTOMATIC CLEANUP --- */}
+            <section>
+                <h3 className="text-xs font-bold text-zinc-500 uppercase tracking-wider mb-4">Automatic Cleanup</h3>
+                <div className="bg-white/5 p-5 rounded-xl border border-white/5 space-y-4">
+                    <p className="text-[10px] text-zinc-500">Applied on startup and after each recording. Favorites and the newest recording are never removed, nor are recordings with jobs in progress.</p>
+                    <div className="grid grid-cols-2 gap-4">
+                        <CustomSelect
+                            label="Keep Newest"
+                            options={[
+                                { value: 0, label: 'All recordings' },
+                                { value: 10, label: '10 recordings' },
+                                { value: 25, label: '25 recordings' },
+                                { value: 50, label: '50 recordings' },
+                                { value: 100, label: '100 recordings' },
+                            ]}
+                            value={settings.retention.keepLast ?? 0}
+                            onChange={(val) => changeRetention({ keepLast: val || null })}
+                        />
+                        <CustomSelect
+                            label="Remove Older Than"
+                            options={[
+                                { value: 0, label: 'Never' },
+                                { value: 30, label: '30 days' },
+                                { value: 90, label: '90 days' },
+                                { value: 180, label: '180 days' },
+                                { value: 365, label: '1 year' },
+                            ]}
+                            value={settings.retention.maxAgeDays ?? 0}
+                            onChange={(val) => changeRetention({ maxAgeDays: val || null })}
+                        />
+                        <CustomSelect
+                            label="Library Size Limit"
+                            options={[
+                                { value: 0, label: 'No limit' },
+                                { value: 10, label: '10 GB' },
+                                { value: 50, label: '50 GB' },
+                                { value: 100, label: '100 GB' },
+                                { value: 250, label: '250 GB' },
+                            ]}
+                            value={settings.retention.maxTotalGb ?? 0}
+                            onChange={(val) => changeRetention({ maxTotalGb: val || null })}
+                        />
+                        <CustomSelect
+                            label="Removed Recordings"
+                            options={[
+                                { value: 'trash', label: 'Move to trash' },
+                                { value: 'archive', label: 'Move to Archive folder' },
+                            ]}
+                            value={settings.retention.action}
+                            onChange={(val) => changeRetention({ action: val })}
+                        />
+                    </div>
+                    <button
+                        onClick={() => {
+                            commands.previewRetention(settings.retention)
+                                .then(setCleanupPreview)
+                                .catch(e => console.error('Failed to preview cleanup:', e));
+                        }}
+                        className="text-xs bg-indigo-500/10 hover:bg-indigo-500/20 text-indigo-400 px-3 py-1.5 rounded-lg border border-indigo-500/20 transition-colors"
+                    >
+                        Preview Cleanup
+                    </button>
+                    {cleanupPreview && (
+                        cleanupPreview.length === 0 ? (
+                            <p className="text-xs text-zinc-500">Nothing would be removed.</p>
+                        ) : (
+                            <div className="space-y-1 max-h-48 overflow-y-auto custom-scrollbar">
+                                {cleanupPreview.map(c => (
+                                    <div key={c.id} className="flex items-center justify-between text-xs bg-black/40 px-3 py-2 rounded-lg">
+                                        <span className="text-zinc-300 truncate mr-4">{c.name}</span>
+                                        <span className="text-zinc-500 shrink-0">
+                                            {(c.sizeBytes / (1024 * 1024)).toFixed(1)} MB • {RETENTION_REASONS[c.reason]}
+                                        </span>
+                                    </div>
+                                ))}
+                            </div>
+                        )
+                    )}
                 </div>
             </section>
 
@@ -143,6 +379,42 @@ export const SettingsPanel = memo(({ settings, updateSettings, availableDevices
                 </div>
             </section>
 
+            {/* --- GLOBAL HOTKEYS --- */}
+            <section>
+                <h3 className="text-xs font-bold text-zinc-500 uppercase tracking-wider mb-4">Global Hotkeys</h3>
+                <div className="bg-white/5 rounded-xl border border-white/5 divide-y divide-white/5">
+                    {HOTKEY_LABELS.map(({ id, label }) => (
+                        <div key={id} className="p-4 flex items-center justify-between">
+                            <div className="text-sm font-medium text-zinc-300">{label}</div>
+                            <div className="flex items-center gap-2">
+                                <input
+                                    readOnly
+                                    value={settings.hotkeys[id] || 'Disabled'}
+                                    onKeyDown={(e) => {
+                                        e.preventDefault();
+                                        const combo = formatHotkey(e);
+                                        if (combo) changeHotkey(id, combo);
+                                    }}
+                                    className="w-40 bg-black/40 px-3 py-1.5 rounded-lg border border-white/5 text-xs font-mono text-zinc-300 text-center focus:border-indigo-500/50 outline-none cursor-pointer"
+                                    title="Click and press a key combination"
+                                />
+                                <button
+                                    onClick={() => changeHotkey(id, '')}
+                                    className="text-[9px] text-zinc-500 font-bold uppercase hover:text-zinc-300 transition-colors"
+                                >
+                                    Clear
+                                </button>
+                            </div>
+                        </div>
+                    ))}
+                    {hotkeyError && (
+                        <div className="p-4 flex items-center gap-2 text-xs text-red-400">
+                            <AlertCircle size={14} className="shrink-0" /> {hotkeyError}
+                        </div>
+                    )}
+                </div>
+            </section>
+
             {/* --- WEBCAM SETTINGS --- */}
             <section>
                 <h3 className="text-xs font-bold text-zinc-500 uppercase tracking-wider mb-4">Webcam Settings</h3>
@@ -269,7 +541,7 @@ export const SettingsPanel = memo(({ settings, updateSettings, availableDevices
                                     <button
                                         onClick={() => {
                                             updateSettings({ ...settings }, true);
-                                            invoke('toggle_webcam', { show: settings.webcamEnabled });
+                                            commands.toggleWebcam(settings.webcamEnabled);
                                         }}
                                         className="text-[9px] text-indigo-400 font-bold uppercase hover:text-indigo-300 transition-colors"
                                     >
@@ -307,6 +579,86 @@ export const SettingsPanel = memo(({ settings, updateSettings, availableDevices
                         value={settings.fps}
                         onChange={(val) => updateSettings({ fps: val })}
                     />
+                    {/* "auto" letterboxes full screens and only shrinks windows and regions */}
+                    <CustomSelect
+                        label="Scaling"
+                        options={[
+                            { value: 'auto', label: "Automatic" },
+                            { value: 'fit', label: "Fit (Letterbox)" },
+                            { value: 'fill', label: "Fill (Crop)" },
+                            { value: 'fitWithin', label: "Shrink Only" },
+                            { value: 'exact', label: "Stretch" }
+                        ]}
+                        value={settings.scaleMode ?? 'auto'}
+                        disabled={!/\d+x\d+/.test(settings.resolution)}
+                        onChange={(val) => updateSettings({ scaleMode: val === 'auto' ? null : val })}
+                    />
+                </div>
+            </section>
+
+            {/* --- RECORDING PROFILES --- */}
+            <section>
+                <h3 className="text-xs font-bold text-zinc-500 uppercase tracking-wider mb-4">Recording Profiles</h3>
+                <div className="bg-white/5 p-5 rounded-xl border border-white/5 space-y-4">
+                    <div className="flex items-center gap-2">
+                        <input
+                            type="text"
+                            value={profileName}
+                            onChange={(e) => setProfileName(e.target.value)}
+                            onKeyDown={(e) => e.key === 'Enter' && profileName.trim() && saveProfile()}
+                            placeholder="Profile name"
+                            className="flex-1 bg-black/40 border border-white/10 rounded-lg px-3 py-1.5 text-xs text-zinc-300 placeholder:text-zinc-600 focus:border-indigo-500/50 outline-none"
+                        />
+                        <button
+                            onClick={saveProfile}
+                            disabled={!profileName.trim()}
+                            className="text-xs bg-indigo-500/10 hover:bg-indigo-500/20 text-indigo-400 px-3 py-1.5 rounded-lg border border-indigo-500/20 transition-colors disabled:opacity-50"
+                        >
+                            Save Current Settings
+                        </button>
+                    </div>
+                    {profiles.length > 0 && (
+                        <div className="space-y-1">
+                            {profiles.map(p => (
+                                <div key={p.id} className="flex items-center justify-between text-xs bg-black/40 px-3 py-2 rounded-lg">
+                                    <span className="text-zinc-300 truncate mr-4">{p.name}</span>
+                                    <button
+                                        onClick={() => runProfileCommand(commands.deleteProfile(p.id))}
+                                        className="text-zinc-500 hover:text-red-400 transition-colors"
+                                    >
+                                        Delete
+                                    </button>
+                                </div>
+                            ))}
+                        </div>
+                    )}
+                    <div className="flex items-center gap-2">
+                        <button
+                            onClick={() => importInputRef.current?.click()}
+                            className="text-xs bg-white/5 hover:bg-white/10 text-zinc-300 px-3 py-1.5 rounded-lg border border-white/10 transition-colors"
+                        >
+                            Import
+                        </button>
+                        <button
+                            onClick={exportProfiles}
+                            disabled={profiles.length === 0}
+                            className="text-xs bg-white/5 hover:bg-white/10 text-zinc-300 px-3 py-1.5 rounded-lg border border-white/10 transition-colors disabled:opacity-50"
+                        >
+                            Export All
+                        </button>
+                        <input
+                            ref={importInputRef}
+                            type="file"
+                            accept="application/json,.json"
+                            className="hidden"
+                            onChange={(e) => {
+                                const file = e.target.files?.[0];
+                                if (file) importProfiles(file);
+                                e.target.value = '';
+                            }}
+                        />
+                    </div>
+                    {profileError && <p className="text-xs text-red-400">{profileError}</p>}
                 </div>
             </section>
 
@@ -317,11 +669,19 @@ export const SettingsPanel = memo(({ settings, updateSettings, availableDevices
                     <div className="p-4 flex items-center justify-between">
                         <div>
                             <div className="text-sm font-medium">Hardware Acceleration</div>
-                            <div className="text-xs text-zinc-500">NVIDIA NVENC H.264 (Detected)</div>
-                        </div>
-                        <div className="flex items-center gap-2 text-indigo-400 text-xs bg-indigo-400/10 px-2 py-1 rounded border border-indigo-400/20">
-                            <Check size={12} /> Active
+                            <div className="text-xs text-zinc-500">H.264 encoder used while recording</div>
                         </div>
+                        <CustomSelect
+                            className="w-48"
+                            options={[
+                                { value: 'x264', label: 'Software (x264)' },
+                                { value: 'nvenc', label: 'NVIDIA NVENC' },
+                                { value: 'qsv', label: 'Intel Quick Sync' },
+                                { value: 'amf', label: 'AMD AMF' }
+                            ]}
+                            value={settings.encoder}
+                            onChange={(val) => updateSettings({ encoder: val })}
+                        />
                     </div>
                     <div className="p-4 flex items-center justify-between">
                         <div>
@@ -331,12 +691,11 @@ export const SettingsPanel = memo(({ settings, updateSettings, availableDevices
                         <CustomSelect
                             className="w-48"
                             options={[
-                                { value: "MP4 (Recommended)", label: "MP4 (Recommended)" },
-                                { value: "MKV", label: "MKV" },
-                                { value: "MOV", label: "MOV" }
+                                { value: 'mp4', label: 'MP4 (Recommended)' },
+                                { value: 'mkv', label: 'MKV (Crash-safe)' }
                             ]}
-                            value={settings.videoFormat}
-                            onChange={(val) => updateSettings({ videoFormat: val })}
+                            value={settings.container}
+                            onChange={(val) => updateSettings({ container: val })}
                         />
                     </div>
                 </div>
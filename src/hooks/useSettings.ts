@@ -1,142 +1,63 @@
 import { useState, useEffect, useRef } from 'react';
-import { load, Store } from '@tauri-apps/plugin-store';
-import { emit, listen } from '@tauri-apps/api/event'; // Import events
+import { commands, events } from '../bindings';
+import type { AppSettings, HotkeyBindings, RetentionPolicy } from '../bindings';
 
-export interface AppSettings {
-    micEnabled: boolean;
-    micDevice: string;
-    systemAudioEnabled: boolean;
-    webcamEnabled: boolean;
-    captureMode: 'fullscreen' | 'window' | 'region';
-    showClicks: boolean;
-    showKeystrokes: boolean;
-    keystrokePosition: 'bottom-left' | 'bottom-right' | 'top-left' | 'top-right' | 'bottom-center';
-    clickColorLeft: string;
-    clickColorRight: string;
-    micVolume: number;
-    systemAudioVolume: number;
-    savePath: string;
-    webcamShape: 'square' | 'portrait' | 'landscape';
-    webcamZoom: number;
-    webcamRoundness: number;
-    webcamWidth: number;
-    webcamHeight: number;
-    webcamDevice: string;
-    resolution: string;
-    fps: number;
-    videoFormat: string;
-}
-
-const DEFAULT_SETTINGS: AppSettings = {
-    micEnabled: true,
-    micDevice: 'Default',
-    systemAudioEnabled: false,
-    webcamEnabled: false,
-    captureMode: 'fullscreen',
-    showClicks: true,
-    showKeystrokes: true,
-    keystrokePosition: 'bottom-left',
-    clickColorLeft: '#a3d95d',
-    clickColorRight: '#f2a93b',
-    micVolume: 1.0,
-    systemAudioVolume: 1.0,
-    savePath: '',
-    webcamShape: 'square',
-    webcamZoom: 1.0,
-    webcamRoundness: 20, // 20 / 2 = 10% border-radius default
-    webcamWidth: 300,
-    webcamHeight: 300,
-    webcamDevice: 'Default',
-    resolution: '1080p FHD (1920x1080)',
-    fps: 60,
-    videoFormat: 'MP4 (Recommended)',
-};
+export type { AppSettings, HotkeyBindings, RetentionPolicy };
 
-let storeInstance: Store | null = null;
+// The backend owns the settings; each window fetches them once before its first render
+let current: AppSettings | null = null;
 
-async function getStore(): Promise<Store> {
-    if (!storeInstance) {
-        storeInstance = await load('settings.json');
-    }
-    return storeInstance;
+export async function loadSettings() {
+    current = await commands.getSettings();
 }
 
 export function useSettings() {
-    const [settings, setSettings] = useState<AppSettings>(DEFAULT_SETTINGS);
-    const [loaded, setLoaded] = useState(false);
-    const saveTimeout = useRef<ReturnType<typeof setTimeout> | null>(null);
-    const syncTimeout = useRef<ReturnType<typeof setTimeout> | null>(null);
-
-    // Load settings on mount and listen for sync events
+    const [settings, setSettings] = useState<AppSettings>(() => {
+        if (!current) throw new Error('useSettings() used before loadSettings()');
+        return current;
+    });
+    // Changes not yet sent to the backend
+    const pending = useRef<Partial<AppSettings>>({});
+    const sendTimeout = useRef<ReturnType<typeof setTimeout> | null>(null);
+
+    // The backend announces every change, including those made in other windows
     useEffect(() => {
-        let unlisten: (() => void) | undefined;
-
-        async function init() {
-            try {
-                const store = await getStore();
-                const saved = await store.get<AppSettings>('settings');
-                if (saved) {
-                    // Force update theme colors
-                    saved.clickColorLeft = '#a3d95d';
-                    saved.clickColorRight = '#f97316';
-                    setSettings(prev => ({ ...prev, ...saved }));
-                }
-            } catch (e) {
-                console.error('Failed to load settings:', e);
-            }
-            setLoaded(true);
-
-            // Listen for updates from other windows/instances
-            unlisten = await listen<AppSettings>('settings-sync', (event) => {
-                setSettings(event.payload);
-            });
-        }
-        init();
-
+        const unlisten = events.settingsChanged.listen((event) => {
+            current = event.payload;
+            setSettings({ ...event.payload, ...pending.current });
+        });
         return () => {
-            if (unlisten) unlisten();
+            unlisten.then(f => f());
         };
     }, []);
 
-    // Save settings whenever they change
-    const updateSettings = async (newSettings: Partial<AppSettings>, immediate = false) => {
-        setSettings(prev => {
-            const updated = { ...prev, ...newSettings };
-
-            // Debounce cross-window sync to prevent performance lag during slider dragging
-            if (syncTimeout.current) clearTimeout(syncTimeout.current);
-
-            if (immediate) {
-                emit('settings-sync', updated).catch(e => console.error("Sync error:", e));
-            } else {
-                syncTimeout.current = setTimeout(() => {
-                    emit('settings-sync', updated).catch(e => console.error("Sync error:", e));
-                }, 16); // Sync at ~60Hz to keep UI responsive without saturating CPU
-            }
-
-            if (saveTimeout.current) {
-                clearTimeout(saveTimeout.current);
-            }
-
-            const performSave = async () => {
-                try {
-                    const store = await getStore();
-                    await store.set('settings', updated);
-                    await store.save();
-                } catch (e) {
-                    console.error('Failed to save settings:', e);
-                }
-            };
-
-            if (immediate) {
-                performSave();
-            } else {
-                saveTimeout.current = setTimeout(performSave, 1500); // 1.5s debounce for disk IO
-            }
+    const send = async () => {
+        const changes = pending.current;
+        pending.current = {};
+        try {
+            await commands.updateSettings(changes);
+        } catch (e) {
+            // Rejected changes never reach `settings-changed`; drop them here too
+            if (current) setSettings({ ...current, ...pending.current });
+            throw e;
+        }
+    };
 
-            return updated;
-        });
+    // Shown right away and sent to the backend at ~60Hz so slider drags stay
+    // smooth. Immediate updates reject with the backend's validation error.
+    const updateSettings = async (changes: Partial<AppSettings>, immediate = false) => {
+        setSettings(prev => ({ ...prev, ...changes }));
+        pending.current = { ...pending.current, ...changes };
+
+        if (sendTimeout.current) clearTimeout(sendTimeout.current);
+        if (immediate) {
+            await send();
+        } else {
+            sendTimeout.current = setTimeout(() => {
+                send().catch(e => console.error('Failed to save settings:', e));
+            }, 16);
+        }
     };
 
-    return { settings, updateSettings, loaded };
+    return { settings, updateSettings };
 }
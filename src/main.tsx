@@ -4,9 +4,17 @@ import "./index.css";
 import App from "./App";
 import WebcamOverlay from "./WebcamOverlay";
 import { getCurrentWindow } from "@tauri-apps/api/window";
+import { loadSettings } from "./hooks/useSettings";
 
 function Root() {
   const [label, setLabel] = useState<string>('detecting');
+  const [settingsLoaded, setSettingsLoaded] = useState(false);
+
+  useEffect(() => {
+    loadSettings()
+      .then(() => setSettingsLoaded(true))
+      .catch(e => console.error('Failed to load settings:', e));
+  }, []);
 
   useEffect(() => {
     async function detectWindow() {
@@ -70,7 +78,7 @@ function Root() {
     detectWindow();
   }, []);
 
-  if (label === 'detecting') {
+  if (label === 'detecting' || !settingsLoaded) {
     return (
       <div className="flex items-center justify-center h-screen w-screen bg-black text-white">
         <h1 className="text-2xl font-bold animate-pulse">Initializing...</h1>